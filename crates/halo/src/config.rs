@@ -1,9 +1,15 @@
 use directories::ProjectDirs;
-use hypraise::desktop::{AppQuery, ExecCommand};
-use hypraise::wm::WindowClass;
+use hypraise::desktop::{AppInfo, AppQuery, Exec, ExecCommand, SETUP_EXEC};
+use hypraise::wm::{ActiveClient, WindowClass};
 use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use palette::{Srgba, WithAlpha};
 use serde::{Deserialize, Serialize};
-use serde_with::DeserializeFromStr;
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::OnceLock;
 use strum::{Display as StrumDisplay, EnumIter, EnumString, IntoEnumIterator};
 use thiserror::Error;
 
@@ -51,20 +57,541 @@ impl Direction {
     }
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Error, Debug)]
+#[error("invalid color '{0}': expected #rgb, #rrggbb, #rrggbbaa or a CSS color name")]
+pub struct ColorParseError(String);
+
+/// A color written in config as `#rgb`, `#rrggbb`, `#rrggbbaa`, or a CSS color name.
+#[derive(Debug, Clone, Copy, PartialEq, SerializeDisplay, DeserializeFromStr)]
+pub struct ColorValue(pub Srgba<f64>);
+
+impl FromStr for ColorValue {
+    type Err = ColorParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        let rgba = match trimmed.strip_prefix('#') {
+            Some(hex) => parse_hex(hex),
+            None => palette::named::from_str(&trimmed.to_ascii_lowercase())
+                .map(|c| c.with_alpha(u8::MAX)),
+        };
+        rgba.map(|c| Self(c.into_format()))
+            .ok_or_else(|| ColorParseError(s.to_string()))
+    }
+}
+
+impl fmt::Display for ColorValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let c: Srgba<u8> = self.0.into_format();
+        write!(
+            f,
+            "#{:02x}{:02x}{:02x}{:02x}",
+            c.red, c.green, c.blue, c.alpha
+        )
+    }
+}
+
+/// Fewer keys than this would leave most subslots without one.
+const MIN_SUB_KEYS: usize = 4;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum SubKeysParseError {
+    #[error("subslot keys need at least {MIN_SUB_KEYS} characters, got {0}")]
+    TooShort(usize),
+    #[error("subslot key '{0}' is listed twice")]
+    Duplicate(char),
+    #[error("'{0}' can't be a subslot key, since it's taken or not a plain character")]
+    Reserved(char),
+}
+
+/// The keys that jump to subslots, in order, written in config as one string like `"aoeu"`.
+#[derive(Debug, Clone, PartialEq, SerializeDisplay, DeserializeFromStr)]
+pub struct SubKeys(pub Vec<char>);
+
+impl FromStr for SubKeys {
+    type Err = SubKeysParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut keys = Vec::new();
+        for c in s.chars().flat_map(char::to_lowercase) {
+            // digits pick slots and `/` toggles labels
+            if c.is_ascii_digit() || c == '/' || c.is_whitespace() || c.is_control() {
+                return Err(SubKeysParseError::Reserved(c));
+            }
+            if keys.contains(&c) {
+                return Err(SubKeysParseError::Duplicate(c));
+            }
+            keys.push(c);
+        }
+        if keys.len() < MIN_SUB_KEYS {
+            return Err(SubKeysParseError::TooShort(keys.len()));
+        }
+        Ok(Self(keys))
+    }
+}
+
+impl fmt::Display for SubKeys {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|c| write!(f, "{c}"))
+    }
+}
+
+fn parse_hex(hex: &str) -> Option<Srgba<u8>> {
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let expanded: String = match hex.len() {
+        3 | 4 => hex.chars().flat_map(|c| [c, c]).collect(),
+        6 | 8 => hex.to_string(),
+        _ => return None,
+    };
+    let value = u32::from_str_radix(&expanded, 16).ok()?;
+    let value = if expanded.len() == 6 {
+        (value << 8) | 0xff
+    } else {
+        value
+    };
+    let [r, g, b, a] = value.to_be_bytes();
+    Some(Srgba::new(r, g, b, a))
+}
+
+/// Per-slot color overrides; unset states fall back to the theme.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+pub struct SlotColors {
+    pub idle: Option<ColorValue>,
+    pub running: Option<ColorValue>,
+    pub hovered: Option<ColorValue>,
+}
+
+impl SlotColors {
+    pub fn is_unset(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Global overrides for the colors taken from the GTK theme; unset ones keep the theme's.
+/// Per-slot `colors` still win over these.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+pub struct MenuColors {
+    pub hovered: Option<ColorValue>,
+    pub running: Option<ColorValue>,
+    /// Idle slots
+    pub default: Option<ColorValue>,
+    pub center_circle: Option<ColorValue>,
+    /// Slots whose app wasn't found
+    pub broken: Option<ColorValue>,
+}
+
+/// Recolors an icon to a single color: `"#fff"`, or `{ color = "#fff", force = true }` to
+/// tint icons that don't look monochrome as well.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum IconTint {
+    Color(ColorValue),
+    Full {
+        color: ColorValue,
+        #[serde(default)]
+        force: bool,
+    },
+}
+
+impl IconTint {
+    pub fn color(&self) -> Srgba<f64> {
+        match self {
+            Self::Color(color) | Self::Full { color, .. } => color.0,
+        }
+    }
+
+    /// Tint even full-color icons, which are otherwise left alone
+    pub fn force(&self) -> bool {
+        matches!(self, Self::Full { force: true, .. })
+    }
+}
+
+/// How the menu is driven.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InputMode {
+    /// Flick the pointer past the ring to activate
+    #[default]
+    Pointer,
+    /// Tap anywhere in the ring to activate the nearest slot; tap the center to cancel
+    Touch,
+}
+
+/// Which slots get their icon dimmed when not hovered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DimMode {
+    /// Dim apps that aren't running, so open apps stand out
+    #[default]
+    Idle,
+    /// Dim apps that are already running, so launchable apps stand out
+    Running,
+}
+
+/// How the ring of slots is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MenuStyle {
+    /// A separate circle per slot
+    #[default]
+    Circles,
+    /// A disc cut into sectors, one per slot direction
+    Pie,
+}
+
+/// What's drawn inside the center circle.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CenterContent {
+    /// An icon name from the theme, or a path to an image
+    Icon(String),
+    /// A short label
+    Text(String),
+    /// The current time, as hours and minutes
+    Clock,
+    /// The name or number of the active workspace
+    Workspace,
+}
+
+/// What flicking a slot past an activation radius does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SlotAction {
+    /// Focus the app's window, launching it if none is open
+    #[default]
+    RunOrRaise,
+    /// Only focus an open window, e.g. to preview it before flicking further
+    Raise,
+    /// Always start a new instance
+    Launch,
+}
+
+/// Distances, in pixels at the reference height, at which the cursor changes band.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+pub struct ActivationConfig {
+    /// The dead zone around the center
+    #[serde(default = "default_inner_radius")]
+    pub inner_radius: f64,
+    /// Crossing this runs `near_action` on the hovered slot
+    #[serde(default = "default_outer_radius")]
+    pub outer_radius: f64,
+    /// An optional third ring that runs `far_action`. While it's set, `near_action` leaves the
+    /// menu open so the flick can carry on outward
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub far_radius: Option<f64>,
+    #[serde(default)]
+    pub near_action: SlotAction,
+    #[serde(default = "default_far_action")]
+    pub far_action: SlotAction,
+    /// Crossing a radius faster than this many pixels per second doesn't activate until the
+    /// pointer slows down inside the band. Unset activates at any speed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_velocity: Option<f64>,
+    /// A click past `outer_radius` does what crossing there would, instead of dismissing
+    #[serde(default = "default_click_activates")]
+    pub click_activates: bool,
+}
+
+fn default_inner_radius() -> f64 {
+    crate::gui::menu::INNER_RADIUS
+}
+
+fn default_outer_radius() -> f64 {
+    crate::gui::menu::OUTER_RADIUS
+}
+
+fn default_far_action() -> SlotAction {
+    SlotAction::Launch
+}
+
+fn default_click_activates() -> bool {
+    true
+}
+
+impl Default for ActivationConfig {
+    fn default() -> Self {
+        Self {
+            inner_radius: default_inner_radius(),
+            outer_radius: default_outer_radius(),
+            far_radius: None,
+            near_action: SlotAction::default(),
+            far_action: default_far_action(),
+            max_velocity: None,
+            click_activates: default_click_activates(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct SlotConfig {
     pub direction: Option<Direction>,
     pub app: Option<AppQuery>,
+    /// A disabled slot is left out of the ring as if its block were deleted
+    #[serde(default = "default_enabled", skip_serializing_if = "is_true")]
+    pub enabled: bool,
+    /// Display name shown instead of the desktop entry's. Doesn't affect matching
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
     pub class: Option<WindowClass>,
-    pub exec: Option<ExecCommand>,
+    /// A shell command line, or an array of the program and its arguments to run without a shell
+    pub exec: Option<Exec>,
+    /// Activating the app while its window is focused hides it instead
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub toggle: bool,
+    /// Never start a second instance, even from a launch action or a quick repeat
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub single_instance: bool,
+    #[serde(default, skip_serializing_if = "SlotColors::is_unset")]
+    pub colors: SlotColors,
+    /// Recolor the icon, overriding the global `icon_tint`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon_tint: Option<IconTint>,
+    /// A URL or path to hand to `xdg-open` instead of running an app
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub open: Option<String>,
+    /// Where Shift+click sends the focused window, instead of the app's own workspace
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<String>,
+    /// Run instead of the slot's action when the left half of the slot is clicked or flicked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub left_exec: Option<Exec>,
+    /// Same as `left_exec`, for the right half
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub right_exec: Option<Exec>,
+    /// Shell command run before activating; activation is skipped if it fails or hangs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_exec: Option<String>,
+    /// Shell command started after a successful activation, without waiting for it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_exec: Option<String>,
+    /// Makes this slot open a nested ring of these slots instead of launching. `app` is
+    /// then only used as its label and icon name
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<SlotConfig>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn is_true(value: &bool) -> bool {
+    *value
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+impl Default for SlotConfig {
+    fn default() -> Self {
+        Self {
+            direction: None,
+            app: None,
+            enabled: default_enabled(),
+            name: None,
+            class: None,
+            exec: None,
+            toggle: false,
+            single_instance: false,
+            colors: SlotColors::default(),
+            icon_tint: None,
+            open: None,
+            workspace: None,
+            left_exec: None,
+            right_exec: None,
+            pre_exec: None,
+            post_exec: None,
+            children: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub struct Config {
     #[serde(default)]
     pub slots: Vec<SlotConfig>,
     #[serde(default)]
     pub show_active_clients: bool,
+    /// Keep each workspace's subslots next to each other, labelled with the workspace name
+    #[serde(default)]
+    pub group_subslots_by_workspace: bool,
+    /// Leave windows that report no class out of the subslots, instead of labelling them by title
+    #[serde(default)]
+    pub skip_classless_windows: bool,
+    /// Only show subslots for windows on the monitor the menu opened on
+    #[serde(default)]
+    pub subslots_on_active_monitor: bool,
+    /// Only accept input in a circle around the menu, letting clicks elsewhere pass through
+    #[serde(default)]
+    pub limit_input_region: bool,
+    #[serde(default)]
+    pub dim_mode: DimMode,
+    #[serde(default)]
+    pub style: MenuStyle,
+    /// Colors to use instead of the GTK theme's
+    #[serde(default)]
+    pub colors: MenuColors,
+    /// Windows on special (scratchpad) workspaces don't count as running
+    #[serde(default)]
+    pub ignore_special_workspaces: bool,
+    #[serde(default)]
+    pub input_mode: InputMode,
+    /// Label slots with the Hyprland keybinds that run `hypraise <app>` for them
+    #[serde(default)]
+    pub show_keybind_hints: bool,
+    /// Activating the same slot again within this many milliseconds is ignored
+    #[serde(default = "default_activation_cooldown_ms")]
+    pub activation_cooldown_ms: u64,
+    /// Draw slots that are launched more often slightly larger
+    #[serde(default)]
+    pub weight_by_usage: bool,
+    #[serde(default)]
+    pub activation: ActivationConfig,
+    /// The most a slot grows when its neighbours are empty
+    #[serde(default = "default_slot_scale_max")]
+    pub slot_scale_max: f64,
+    /// How quickly slots grow with the free space around them
+    #[serde(default = "default_slot_scale_curve")]
+    pub slot_scale_curve: f64,
+    /// After right-click closes an app, focus the window that had focus before the menu opened
+    #[serde(default)]
+    pub return_focus_after_close: bool,
+    /// Hide the menu when its window loses focus, instead of only dropping the hover
+    #[serde(default)]
+    pub hide_on_focus_loss: bool,
+    /// Draw a soft shadow under icons so light ones stand out over bright wallpapers
+    #[serde(default)]
+    pub icon_shadow: bool,
+    /// Open the menu with only the slots whose app is running; Tab brings back the rest
+    #[serde(default)]
+    pub running_only: bool,
+    /// Recolor monochrome icons to match a theme; slots can override it
+    #[serde(default)]
+    pub icon_tint: Option<IconTint>,
+    /// Keep running states and subslots current while the menu stays open
+    #[serde(default)]
+    pub live_client_updates: bool,
+    /// Keep the pointer on the menu's monitor while it's open
+    #[serde(default)]
+    pub confine_pointer: bool,
+    /// Start apps in their own `systemd-run --user --scope` unit, when systemd-run is installed
+    #[serde(default)]
+    pub use_systemd_scope: bool,
+    /// Also find windows by the class they opened with, for apps that change theirs later
+    #[serde(default)]
+    pub match_initial_class: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub center_content: Option<CenterContent>,
+    /// Opacity of the rings above an open submenu, drawn faded behind it. 0 hides them
+    #[serde(default = "default_parent_ring_alpha")]
+    pub parent_ring_alpha: f64,
+    /// Caps the ring's width in pixels, however tall the monitor is
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_diameter_px: Option<f64>,
+    /// Keeps the ring at least this many pixels wide on small monitors
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_diameter_px: Option<f64>,
+    /// How long subslots take to fan out from the center when the menu shows. 0 shows them in
+    /// place at once
+    #[serde(default)]
+    pub subslot_fan_out_ms: u64,
+    /// The keys that jump to subslots, in order; the QWERTY left-hand cluster when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subslot_keys: Option<SubKeys>,
+    /// Announce hovers and activations on a read-only socket, for bars and scripts. Read at
+    /// startup
+    #[serde(default)]
+    pub broadcast_events: bool,
+    /// Refuse to load a config with slots that would be ignored, instead of warning about them
+    #[serde(default)]
+    pub strict_slots: bool,
+    /// Slots that replace the base ones while a window of the class they're keyed by has focus
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub contexts: HashMap<String, ContextConfig>,
+}
+
+/// A `[contexts.<class>]` table.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize)]
+pub struct ContextConfig {
+    #[serde(default)]
+    pub slots: Vec<SlotConfig>,
+}
+
+impl Config {
+    /// The slots for a menu opened over `focused`: the base slots, with those of the context
+    /// matching one of its classes replacing the ones in the same direction.
+    pub fn slots_for(&self, focused: Option<&ActiveClient>) -> Vec<SlotConfig> {
+        let context = focused.and_then(|client| {
+            client.match_classes().find_map(|class| {
+                self.contexts
+                    .iter()
+                    .find(|(key, _)| key.eq_ignore_ascii_case(class.as_str()))
+                    .map(|(_, context)| context)
+            })
+        });
+        let mut slots = self.slots.clone();
+        for cfg in context.map_or(&[][..], |c| &c.slots) {
+            slots.retain(|base| cfg.direction.is_none() || base.direction != cfg.direction);
+            slots.push(cfg.clone());
+        }
+        slots
+    }
+}
+
+fn default_slot_scale_max() -> f64 {
+    crate::gui::menu::SLOT_SCALE_MAX
+}
+
+fn default_slot_scale_curve() -> f64 {
+    crate::gui::menu::SLOT_SCALE_CURVE
+}
+
+fn default_parent_ring_alpha() -> f64 {
+    crate::gui::menu::PARENT_RING_ALPHA
+}
+
+fn default_activation_cooldown_ms() -> u64 {
+    300
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            slots: Vec::new(),
+            show_active_clients: false,
+            group_subslots_by_workspace: false,
+            skip_classless_windows: false,
+            subslots_on_active_monitor: false,
+            limit_input_region: false,
+            dim_mode: DimMode::default(),
+            style: MenuStyle::default(),
+            colors: MenuColors::default(),
+            ignore_special_workspaces: false,
+            input_mode: InputMode::default(),
+            show_keybind_hints: false,
+            activation_cooldown_ms: default_activation_cooldown_ms(),
+            weight_by_usage: false,
+            activation: ActivationConfig::default(),
+            slot_scale_max: default_slot_scale_max(),
+            slot_scale_curve: default_slot_scale_curve(),
+            return_focus_after_close: false,
+            hide_on_focus_loss: false,
+            icon_shadow: false,
+            running_only: false,
+            icon_tint: None,
+            live_client_updates: false,
+            confine_pointer: false,
+            use_systemd_scope: false,
+            match_initial_class: false,
+            center_content: None,
+            parent_ring_alpha: default_parent_ring_alpha(),
+            max_diameter_px: None,
+            min_diameter_px: None,
+            subslot_fan_out_ms: 0,
+            subslot_keys: None,
+            broadcast_events: false,
+            strict_slots: false,
+            contexts: HashMap::new(),
+        }
+    }
 }
 
 #[derive(Error, Debug)]
@@ -75,51 +602,245 @@ pub enum ConfigError {
     Config(#[from] config::ConfigError),
     #[error("Notify error: {0}")]
     Notify(#[from] notify::Error),
+    #[error("{}", .0.join("; "))]
+    IgnoredSlots(Vec<String>),
+}
+
+static CONFIG_PATH_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Points the daemon at a config file other than the default location. Only the first call
+/// takes effect; relative paths are made absolute so the watcher can match change events.
+pub fn set_config_path(path: PathBuf) {
+    let path = std::path::absolute(&path).unwrap_or(path);
+    let _ = CONFIG_PATH_OVERRIDE.set(path);
 }
 
 pub fn get_config_path() -> Result<std::path::PathBuf, ConfigError> {
+    if let Some(path) = CONFIG_PATH_OVERRIDE.get() {
+        return Ok(path.clone());
+    }
     let proj_dirs =
         ProjectDirs::from("org", "troia", "halo").ok_or(ConfigError::ConfigDirNotFound)?;
     Ok(proj_dirs.config_dir().join("config.toml"))
 }
 
+/// Where drop-in files for `config_path` go: `config.d` next to it.
+pub fn drop_in_dir(config_path: &Path) -> Option<PathBuf> {
+    config_path.parent().map(|dir| dir.join("config.d"))
+}
+
+/// The `*.toml` files in `config_path`'s [`drop_in_dir`], sorted by name.
+fn drop_in_files(config_path: &Path) -> Vec<PathBuf> {
+    let Some(entries) = drop_in_dir(config_path).and_then(|dir| fs_err::read_dir(dir).ok()) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml") && path.is_file())
+        .collect();
+    files.sort();
+    files
+}
+
+/// Just the `slots` of one file, since a later source's array replaces an earlier one.
+fn file_slots(path: &Path) -> Result<Vec<SlotConfig>, ConfigError> {
+    #[derive(Deserialize)]
+    struct Slots {
+        #[serde(default)]
+        slots: Vec<SlotConfig>,
+    }
+
+    let slots: Slots = config::Config::builder()
+        .add_source(config::File::from(path).required(false))
+        .build()?
+        .try_deserialize()?;
+    Ok(slots.slots)
+}
+
 pub fn load_config() -> Result<Config, ConfigError> {
-    let config_path = get_config_path()?;
+    load_config_from(&get_config_path()?)
+}
 
-    let s = config::Config::builder()
-        .add_source(config::File::from(config_path).required(false))
+/// Loads `config_path` with its drop-in files merged over it in order, then the `HALO_`
+/// environment variables. Later values replace earlier ones and tables are merged key by key,
+/// except that every file's `slots` are appended.
+fn load_config_from(config_path: &Path) -> Result<Config, ConfigError> {
+    let drop_ins = drop_in_files(config_path);
+    let mut builder =
+        config::Config::builder().add_source(config::File::from(config_path).required(false));
+    for path in &drop_ins {
+        builder = builder.add_source(config::File::from(path.as_path()));
+    }
+    let s = builder
         .add_source(config::Environment::with_prefix("HALO"))
         .build()?;
 
-    Ok(s.try_deserialize()?)
+    let mut config: Config = s.try_deserialize()?;
+    if !drop_ins.is_empty() {
+        config.slots = file_slots(config_path)?;
+        for path in &drop_ins {
+            config.slots.extend(file_slots(path)?);
+        }
+    }
+    let mut ignored = ignored_slots(&config.slots);
+    for (class, context) in &config.contexts {
+        ignored.extend(
+            ignored_slots(&context.slots)
+                .into_iter()
+                .map(|warning| format!("In context '{class}': {warning}")),
+        );
+    }
+    if config.strict_slots && !ignored.is_empty() {
+        return Err(ConfigError::IgnoredSlots(ignored));
+    }
+    for warning in ignored {
+        log::warn!("{}", warning);
+    }
+    Ok(config)
+}
+
+/// Describes every slot the menu won't show: those without a direction, and those sharing a
+/// direction with an earlier one in the same ring, which replaces it. Each ring has one place
+/// per [`Direction`], so at most that many slots of it are shown. Nested rings are checked too.
+pub fn ignored_slots(slots: &[SlotConfig]) -> Vec<String> {
+    fn label(cfg: &SlotConfig) -> String {
+        cfg.app
+            .as_ref()
+            .map(|q| q.to_string())
+            .or_else(|| cfg.open.clone())
+            .unwrap_or_else(|| "unnamed slot".to_string())
+    }
+
+    let mut seen: HashMap<Direction, &SlotConfig> = HashMap::new();
+    let mut warnings = Vec::new();
+    for cfg in slots.iter().filter(|cfg| cfg.enabled) {
+        let Some(dir) = cfg.direction else {
+            warnings.push(format!(
+                "Slot '{}' has no direction and is ignored",
+                label(cfg)
+            ));
+            continue;
+        };
+        if let Some(earlier) = seen.insert(dir, cfg) {
+            warnings.push(format!(
+                "Slot {} is configured twice: '{}' replaces '{}'",
+                dir,
+                label(cfg),
+                label(earlier)
+            ));
+        }
+        warnings.extend(ignored_slots(&cfg.children));
+    }
+    warnings
+}
+
+/// Counts the slots turned off with `enabled = false`, nested ones included. The slots of a
+/// disabled nested ring aren't counted on their own.
+pub fn disabled_slots(slots: &[SlotConfig]) -> usize {
+    slots
+        .iter()
+        .map(|cfg| {
+            if cfg.enabled {
+                disabled_slots(&cfg.children)
+            } else {
+                1
+            }
+        })
+        .sum()
+}
+
+/// A starting config made from the open windows, for `export-config`.
+#[derive(Debug, Default, PartialEq)]
+pub struct ConfigTemplate {
+    /// One slot per app, in clockwise order from North
+    pub slots: Vec<SlotConfig>,
+    /// Window classes no desktop entry was found for
+    pub unresolved: Vec<WindowClass>,
+    /// Apps past the last direction
+    pub left_out: Vec<String>,
+}
+
+impl ConfigTemplate {
+    /// Gives each app with an open window a slot, in the order Hyprland lists the windows.
+    /// Classes are resolved with `find`, as slots are, so a slot matches the app it came from.
+    pub fn from_clients(
+        clients: &[ActiveClient],
+        find: impl Fn(&AppQuery) -> Option<AppInfo>,
+    ) -> Self {
+        let mut template = Self::default();
+        let mut apps: Vec<AppInfo> = Vec::new();
+        for client in clients.iter().filter(|c| !c.class.is_empty()) {
+            match find(&AppQuery::new(client.class.as_str())) {
+                Some(app) if apps.iter().any(|a| a.name == app.name) => {}
+                Some(app) => apps.push(app),
+                None if template.unresolved.contains(&client.class) => {}
+                None => template.unresolved.push(client.class.clone()),
+            }
+        }
+
+        let mut directions = Direction::iter();
+        for app in apps {
+            match directions.next() {
+                Some(direction) => template.slots.push(SlotConfig {
+                    direction: Some(direction),
+                    app: Some(AppQuery::new(app.name.as_str())),
+                    ..Default::default()
+                }),
+                None => template.left_out.push(app.name.to_string()),
+            }
+        }
+        template
+    }
+
+    /// The slots as TOML, after comments listing the windows that got none.
+    pub fn to_toml(&self) -> Result<String, toml::ser::Error> {
+        #[derive(Serialize)]
+        struct Slots<'a> {
+            slots: &'a [SlotConfig],
+        }
+
+        let mut out = String::new();
+        if !self.unresolved.is_empty() {
+            let classes: Vec<_> = self.unresolved.iter().map(|c| c.as_str()).collect();
+            out += &format!("# No desktop entry found for: {}\n", classes.join(", "));
+        }
+        if !self.left_out.is_empty() {
+            out += &format!("# No direction left for: {}\n", self.left_out.join(", "));
+        }
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out += &toml::to_string(&Slots { slots: &self.slots })?;
+        Ok(out)
+    }
 }
 
 pub fn load_or_setup() -> Config {
+    // drop-in files a package installed are a config too, even without the main file
     if let Ok(path) = get_config_path()
         && !path.exists()
+        && drop_in_files(&path).is_empty()
     {
-        return Config {
-            slots: vec![SlotConfig {
-                direction: Some(Direction::North),
-                app: Some(AppQuery::from("Setup".to_string())),
-                class: Some(WindowClass::from("halo-setup".to_string())),
-                exec: Some(ExecCommand::from("HALO_SETUP".to_string())),
-            }],
-            show_active_clients: false,
-        };
+        return setup_config();
     }
 
-    match load_config() {
-        Ok(c) => c,
-        Err(_) => Config {
-            slots: vec![SlotConfig {
-                direction: Some(Direction::North),
-                app: Some(AppQuery::from("Setup".to_string())),
-                class: Some(WindowClass::from("halo-setup".to_string())),
-                exec: Some(ExecCommand::from("HALO_SETUP".to_string())),
-            }],
-            show_active_clients: false,
-        },
+    load_config().unwrap_or_else(|e| {
+        log::error!("Failed to load config, starting in setup mode: {}", e);
+        setup_config()
+    })
+}
+
+fn setup_config() -> Config {
+    Config {
+        slots: vec![SlotConfig {
+            direction: Some(Direction::North),
+            app: Some(AppQuery::from("Setup".to_string())),
+            class: Some(WindowClass::from("halo-setup".to_string())),
+            exec: Some(ExecCommand::new(SETUP_EXEC).into()),
+            ..Default::default()
+        }],
+        ..Default::default()
     }
 }
 
@@ -135,6 +856,20 @@ pub fn write_default_config() -> std::io::Result<std::path::PathBuf> {
     Ok(path)
 }
 
+/// What the setup slot does instead of running its [`SETUP_EXEC`]: writes the default config
+/// if there is none yet and opens it.
+pub fn open_setup() {
+    match write_default_config() {
+        Ok(path) => {
+            let mut xdg_open = std::process::Command::new("xdg-open");
+            if let Err(e) = hypraise::wm::spawn_detached(xdg_open.arg(&path)) {
+                log::error!("Failed to open {}: {}", path.display(), e);
+            }
+        }
+        Err(e) => log::error!("Failed to write the default config: {}", e),
+    }
+}
+
 const DEFAULT_CONFIG: &str = include_str!("default_config.toml");
 
 use crate::events::AppEvent;
@@ -173,10 +908,25 @@ pub async fn run_async_watcher(tx: Sender<AppEvent>) {
         }
     };
 
+    let drop_in_dir = drop_in_dir(&config_path);
+    let is_config_file = |path: &Path| {
+        path == config_path
+            || drop_in_dir.as_deref().is_some_and(|dir| {
+                path == dir
+                    || path.parent() == Some(dir)
+                        && path.extension().is_some_and(|ext| ext == "toml")
+            })
+    };
+    // neither watch is recursive, as `--config` may point into a directory as big as $HOME
     if let Err(e) = watcher.watch(&config_dir, RecursiveMode::NonRecursive) {
         log::error!("Failed to watch config directory: {}", e);
         return;
     }
+    if let Some(dir) = drop_in_dir.as_deref().filter(|dir| dir.is_dir())
+        && let Err(e) = watcher.watch(dir, RecursiveMode::NonRecursive)
+    {
+        log::warn!("Failed to watch {}: {}", dir.display(), e);
+    }
 
     while let Ok(res) = bridge_rx.recv().await {
         match res {
@@ -185,9 +935,18 @@ pub async fn run_async_watcher(tx: Sender<AppEvent>) {
                     event.kind,
                     EventKind::Modify(_) | EventKind::Create(_) | EventKind::Remove(_)
                 );
+                // a `config.d` created after startup gets its watch now; the reload below
+                // picks up whatever was put in it before that
+                if matches!(event.kind, EventKind::Create(_))
+                    && let Some(dir) = drop_in_dir.as_deref()
+                    && event.paths.iter().any(|p| p == dir)
+                    && let Err(e) = watcher.watch(dir, RecursiveMode::NonRecursive)
+                {
+                    log::warn!("Failed to watch {}: {}", dir.display(), e);
+                }
 
                 if meaningful_event
-                    && event.paths.iter().any(|p| p == &config_path)
+                    && event.paths.iter().any(|p| is_config_file(p))
                     && tx.send(AppEvent::ConfigReload).await.is_err()
                 {
                     break;
@@ -201,6 +960,28 @@ pub async fn run_async_watcher(tx: Sender<AppEvent>) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use hypraise::wm::{Address, ClientTitle};
+
+    fn client(address: &str, class: &str) -> ActiveClient {
+        ActiveClient {
+            address: Address::new(address),
+            class: WindowClass::new(class),
+            initial_class: WindowClass::new(class),
+            title: ClientTitle::new(""),
+            workspace_id: 1,
+            workspace_name: "1".to_string(),
+            monitor: None,
+            focused: false,
+            urgent: false,
+        }
+    }
+
+    fn parse(toml: &str) -> Result<Config, config::ConfigError> {
+        config::Config::builder()
+            .add_source(config::File::from_str(toml, config::FileFormat::Toml))
+            .build()?
+            .try_deserialize()
+    }
 
     #[test]
     fn test_direction_deserialization() {
@@ -220,4 +1001,481 @@ mod tests {
             assert_eq!(deserialized, expected);
         }
     }
+
+    #[test]
+    fn test_default_config_round_trips() {
+        let config = parse(DEFAULT_CONFIG).expect("default config should parse");
+        assert!(!config.slots.is_empty());
+
+        let serialized = toml::to_string(&config).unwrap();
+        let reparsed = parse(&serialized).expect("serialized config should parse");
+        assert_eq!(reparsed.slots, config.slots);
+        assert_eq!(reparsed, config);
+    }
+
+    #[test]
+    fn test_center_content_forms() {
+        let content = |toml: &str| parse(toml).unwrap().center_content;
+        assert_eq!(content(""), None);
+        assert_eq!(
+            content("center_content = { icon = \"archlinux-logo\" }"),
+            Some(CenterContent::Icon("archlinux-logo".into()))
+        );
+        assert_eq!(
+            content("center_content = { text = \"λ\" }"),
+            Some(CenterContent::Text("λ".into()))
+        );
+        assert_eq!(
+            content("center_content = \"clock\""),
+            Some(CenterContent::Clock)
+        );
+        assert_eq!(
+            content("center_content = \"workspace\""),
+            Some(CenterContent::Workspace)
+        );
+    }
+
+    #[test]
+    fn test_nested_children_parse() {
+        let config = parse(
+            r#"
+            [[slots]]
+            direction = "North"
+            app = "Dev"
+
+            [[slots.children]]
+            direction = "East"
+            app = "code"
+
+            [[slots.children]]
+            direction = "West"
+            app = "ghostty"
+            "#,
+        )
+        .unwrap();
+
+        let children = &config.slots[0].children;
+        assert_eq!(children.len(), 2);
+        assert_eq!(children[1].direction, Some(Direction::West));
+        assert_eq!(children[1].app, Some(AppQuery::new("ghostty")));
+        assert!(children[0].children.is_empty());
+    }
+
+    #[test]
+    fn test_ignored_slots() {
+        let config = parse(
+            r#"
+            [[slots]]
+            direction = "North"
+            app = "firefox"
+
+            [[slots]]
+            direction = "n"
+            app = "zen"
+
+            [[slots]]
+            direction = "South"
+            app = "ghostty"
+
+            [[slots]]
+            app = "dolphin"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            ignored_slots(&config.slots),
+            vec![
+                "Slot North is configured twice: 'zen' replaces 'firefox'".to_string(),
+                "Slot 'dolphin' has no direction and is ignored".to_string(),
+            ]
+        );
+        assert!(ignored_slots(&config.slots[1..3]).is_empty());
+    }
+
+    #[test]
+    fn test_disabled_slots() {
+        let config = parse(
+            r#"
+            [[slots]]
+            direction = "North"
+            app = "firefox"
+            enabled = false
+
+            [[slots]]
+            direction = "North"
+            app = "zen"
+
+            [[slots]]
+            direction = "South"
+            app = "Tools"
+
+            [[slots.children]]
+            direction = "East"
+            app = "ghostty"
+            enabled = false
+            "#,
+        )
+        .unwrap();
+
+        assert!(!config.slots[0].enabled);
+        assert!(config.slots[1].enabled);
+        assert_eq!(disabled_slots(&config.slots), 2);
+        // a disabled slot doesn't take up its direction
+        assert!(ignored_slots(&config.slots).is_empty());
+    }
+
+    #[test]
+    fn test_drop_in_files_merge_over_config() {
+        let dir = std::env::temp_dir().join(format!("halo-config-{}", std::process::id()));
+        let drop_ins = dir.join("config.d");
+        fs_err::create_dir_all(&drop_ins).unwrap();
+        let config_path = dir.join("config.toml");
+        let write = |path: PathBuf, toml: &str| fs_err::write(path, toml).unwrap();
+        write(
+            config_path.clone(),
+            "style = \"pie\"\nicon_shadow = true\n[[slots]]\ndirection = \"North\"\napp = \"zen\"\n",
+        );
+        write(
+            drop_ins.join("20-media.toml"),
+            "icon_shadow = false\n[[slots]]\ndirection = \"South\"\napp = \"mpv\"\n",
+        );
+        write(
+            drop_ins.join("10-tools.toml"),
+            "[[slots]]\ndirection = \"East\"\napp = \"kitty\"\n",
+        );
+        write(drop_ins.join("notes.txt"), "not = \"config\"");
+
+        let config = load_config_from(&config_path);
+        let _ = fs_err::remove_dir_all(&dir);
+        let config = config.unwrap();
+
+        let apps: Vec<_> = config
+            .slots
+            .iter()
+            .map(|s| s.app.as_ref().unwrap().to_string())
+            .collect();
+        assert_eq!(apps, ["zen", "kitty", "mpv"]);
+        assert_eq!(config.style, MenuStyle::Pie);
+        // the last file to set a value wins
+        assert!(!config.icon_shadow);
+    }
+
+    #[test]
+    fn test_context_slots_replace_base_ones() {
+        let config = parse(
+            r#"
+            [[slots]]
+            direction = "North"
+            app = "firefox"
+
+            [[slots]]
+            direction = "South"
+            app = "kitty"
+
+            [[contexts.Code.slots]]
+            direction = "South"
+            app = "gitg"
+
+            [[contexts.Code.slots]]
+            direction = "East"
+            app = "meld"
+            "#,
+        )
+        .unwrap();
+        let focused = |class: &str| ActiveClient {
+            focused: true,
+            ..client("0x1", class)
+        };
+        let apps = |slots: Vec<SlotConfig>| -> Vec<_> {
+            slots
+                .into_iter()
+                .map(|s| (s.direction.unwrap(), s.app.unwrap().to_string()))
+                .collect()
+        };
+
+        assert_eq!(config.slots_for(None), config.slots);
+        assert_eq!(config.slots_for(Some(&focused("kitty"))), config.slots);
+        assert_eq!(
+            apps(config.slots_for(Some(&focused("code")))),
+            [
+                (Direction::North, "firefox".to_string()),
+                (Direction::South, "gitg".to_string()),
+                (Direction::East, "meld".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_config_template_from_clients() {
+        use hypraise::desktop::{AppName, find_desktop_entry_in_list};
+
+        let window = |class: &str| client(class, class);
+        let entry = |name: &str, class: &str| {
+            AppInfo::from_parts(
+                AppName::new(name),
+                PathBuf::new(),
+                WindowClass::new(class),
+                ExecCommand::new(class),
+            )
+        };
+        let mut entries = vec![entry("Firefox", "firefox"), entry("Kitty", "kitty")];
+        entries.extend((0..8).map(|i| entry(&format!("App {i}"), &format!("app{i}"))));
+        let find = |query: &AppQuery| find_desktop_entry_in_list(query, &entries);
+
+        let mut clients = vec![
+            window("kitty"),
+            window("firefox"),
+            window("kitty"),
+            window("mystery"),
+        ];
+        let template = ConfigTemplate::from_clients(&clients, find);
+        let apps: Vec<_> = template
+            .slots
+            .iter()
+            .map(|s| (s.direction.unwrap(), s.app.as_ref().unwrap().as_str()))
+            .collect();
+        assert_eq!(
+            apps,
+            [
+                (Direction::North, "Kitty"),
+                (Direction::NorthEast, "Firefox")
+            ]
+        );
+        assert_eq!(template.unresolved, [WindowClass::new("mystery")]);
+
+        let toml = template.to_toml().unwrap();
+        assert!(toml.starts_with("# No desktop entry found for: mystery\n"));
+        // only what differs from the defaults is written out
+        assert!(toml.contains("app = \"Kitty\"\n\n[[slots]]"));
+        let reparsed = parse(&toml).unwrap();
+        assert_eq!(reparsed.slots, template.slots);
+
+        clients.extend((0..8).map(|i| window(&format!("app{i}"))));
+        let template = ConfigTemplate::from_clients(&clients, find);
+        assert_eq!(template.slots.len(), 8);
+        assert_eq!(template.left_out, ["App 6", "App 7"]);
+    }
+
+    #[test]
+    fn test_exec_string_and_array_forms() {
+        let config = parse(
+            r#"
+            [[slots]]
+            direction = "North"
+            exec = "firefox --new-window"
+
+            [[slots]]
+            direction = "South"
+            exec = ["flatpak", "run", "org.example.App"]
+
+            [[slots]]
+            direction = "East"
+            exec = { first_of = [["wezterm", "start"], "kitty -1"] }
+
+            [[slots]]
+            direction = "West"
+            exec = { first_of = ["wezterm", "kitty", "alacritty"] }
+
+            [[slots]]
+            direction = "NorthEast"
+            exec = ["wezterm", "kitty", "alacritty"]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.slots[0].exec,
+            Some(Exec::Shell(ExecCommand::new("firefox --new-window")))
+        );
+        assert_eq!(
+            config.slots[1].exec,
+            Some(Exec::Args(vec![
+                "flatpak".into(),
+                "run".into(),
+                "org.example.App".into()
+            ]))
+        );
+        assert_eq!(
+            config.slots[2].exec,
+            Some(Exec::FirstOf {
+                first_of: vec![
+                    Exec::Args(vec!["wezterm".into(), "start".into()]),
+                    Exec::Shell(ExecCommand::new("kitty -1")),
+                ]
+            })
+        );
+        assert_eq!(
+            config.slots[3].exec,
+            Some(Exec::FirstOf {
+                first_of: ["wezterm", "kitty", "alacritty"]
+                    .map(|c| Exec::Shell(ExecCommand::new(c)))
+                    .to_vec()
+            })
+        );
+        // without the key, plain strings are one command and its arguments
+        assert_eq!(
+            config.slots[4].exec,
+            Some(Exec::Args(vec![
+                "wezterm".into(),
+                "kitty".into(),
+                "alacritty".into()
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_activation_bands() {
+        let config = parse("").unwrap();
+        assert_eq!(config.activation, ActivationConfig::default());
+        assert_eq!(config.activation.far_radius, None);
+
+        let config = parse(
+            r#"
+            [activation]
+            outer_radius = 110
+            far_radius = 220.5
+            near_action = "raise"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.activation.inner_radius, default_inner_radius());
+        assert_eq!(config.activation.outer_radius, 110.0);
+        assert_eq!(config.activation.far_radius, Some(220.5));
+        assert_eq!(config.activation.near_action, SlotAction::Raise);
+        assert_eq!(config.activation.far_action, SlotAction::Launch);
+
+        assert_eq!(config.activation.max_velocity, None);
+        assert!(config.activation.click_activates);
+
+        assert!(parse("[activation]\nfar_action = \"explode\"").is_err());
+    }
+
+    #[test]
+    fn test_missing_cooldown_uses_default() {
+        let config = parse("show_active_clients = true").unwrap();
+        assert_eq!(config.activation_cooldown_ms, 300);
+        assert_eq!(
+            config.activation_cooldown_ms,
+            Config::default().activation_cooldown_ms
+        );
+
+        let config = parse("activation_cooldown_ms = 0").unwrap();
+        assert_eq!(config.activation_cooldown_ms, 0);
+    }
+
+    #[test]
+    fn test_color_parsing() {
+        let cases = vec![
+            ("#ff0000", Srgba::new(255u8, 0, 0, 255)),
+            ("#F00", Srgba::new(255, 0, 0, 255)),
+            ("#00ff0080", Srgba::new(0, 255, 0, 128)),
+            ("#0f08", Srgba::new(0, 255, 0, 136)),
+            ("RebeccaPurple", Srgba::new(102, 51, 153, 255)),
+        ];
+
+        for (input, expected) in cases {
+            let parsed: ColorValue = input.parse().unwrap();
+            assert_eq!(Srgba::<u8>::from_format(parsed.0), expected, "{input}");
+        }
+
+        for bad in ["#12345", "#gg0000", "notacolor", ""] {
+            assert!(bad.parse::<ColorValue>().is_err(), "{bad}");
+        }
+    }
+
+    #[test]
+    fn test_subslot_keys() {
+        let keys = |toml: &str| parse(toml).map(|c| c.subslot_keys);
+        assert_eq!(keys("").unwrap(), None);
+        assert_eq!(
+            keys("subslot_keys = \"AOEUhtns\"")
+                .unwrap()
+                .unwrap()
+                .to_string(),
+            "aoeuhtns"
+        );
+        assert!(keys("subslot_keys = \"aoeua\"").is_err());
+
+        assert_eq!("arst".parse::<SubKeys>().unwrap().0, ['a', 'r', 's', 't']);
+        assert_eq!(
+            "arsa".parse::<SubKeys>(),
+            Err(SubKeysParseError::Duplicate('a'))
+        );
+        assert_eq!(
+            "ars".parse::<SubKeys>(),
+            Err(SubKeysParseError::TooShort(3))
+        );
+        assert_eq!(
+            "ars1".parse::<SubKeys>(),
+            Err(SubKeysParseError::Reserved('1'))
+        );
+    }
+
+    #[test]
+    fn test_partial_slot_colors() {
+        let toml = r##"
+            [[slots]]
+            direction = "n"
+            app = "zen"
+            colors = { running = "#112233" }
+        "##;
+        let config = parse(toml).unwrap();
+
+        let colors = config.slots[0].colors;
+        assert_eq!(colors.running, Some("#112233".parse().unwrap()));
+        assert_eq!(colors.idle, None);
+        assert_eq!(colors.hovered, None);
+    }
+
+    #[test]
+    fn test_invalid_slot_color_is_rejected() {
+        let toml = r##"
+            [[slots]]
+            direction = "n"
+            colors = { idle = "#nope" }
+        "##;
+        let err = parse(toml).unwrap_err();
+        assert!(err.to_string().contains("invalid color '#nope'"), "{err}");
+    }
+
+    #[test]
+    fn test_icon_tint_forms() {
+        let toml = r##"
+            icon_tint = "white"
+
+            [[slots]]
+            direction = "n"
+            icon_tint = { color = "#ff000080", force = true }
+
+            [[slots]]
+            direction = "s"
+        "##;
+        let config = parse(toml).unwrap();
+
+        let global = config.icon_tint.unwrap();
+        assert_eq!(global.color(), Srgba::new(1.0, 1.0, 1.0, 1.0));
+        assert!(!global.force());
+        let forced = config.slots[0].icon_tint.unwrap();
+        assert!(forced.force());
+        assert_eq!(forced.color().red, 1.0);
+        assert_eq!(config.slots[1].icon_tint, None);
+    }
+
+    #[test]
+    fn test_menu_colors() {
+        let toml = r##"
+            [colors]
+            hovered = "#ff8800"
+            broken = "crimson"
+        "##;
+        let config = parse(toml).unwrap();
+
+        let hovered = config.colors.hovered.unwrap().0;
+        assert_eq!((hovered.red, hovered.blue, hovered.alpha), (1.0, 0.0, 1.0));
+        assert!(config.colors.broken.is_some());
+        assert_eq!(config.colors.running, None);
+        assert!(parse("[colors]\ndefault = \"#12\"").is_err());
+        assert_eq!(parse("").unwrap().colors, MenuColors::default());
+    }
 }
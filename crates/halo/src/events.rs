@@ -1,10 +1,169 @@
-use hypraise::wm::Point;
+use crate::config::Direction;
+use async_channel::{Receiver, Sender};
+use hypraise::desktop::AppQuery;
+use hypraise::wm::{ActiveClient, Point};
+use std::collections::VecDeque;
+use std::mem::discriminant;
+
+/// Most events held back while the GUI is busy; past this, new events are dropped.
+pub const MAX_PENDING: usize = 32;
+
+/// Where and how large `show --at <x>,<y> --scale <factor>` wants the ring, e.g. for a compact
+/// ring opened from a bar button.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Placement {
+    /// The ring's center in logical pixels from the focused monitor's top-left, instead of
+    /// the cursor
+    pub at: Option<Point>,
+    /// Multiplies the scale the monitor's size gives the ring
+    pub scale: Option<f64>,
+}
 
 #[derive(Debug, Clone)]
 pub enum AppEvent {
     Show,
+    /// Show with the center or size given instead of the usual ones
+    ShowAt(Placement),
+    /// Show only the slots whose app is running
+    ShowRunning,
+    /// Show a ring of the apps in this desktop entry category
+    ShowCategory(String),
+    Switch,
     Hide,
-    Click(u32),
+    Click(u32, Point),
     CursorMove(Point),
     ConfigReload,
+    RefreshIcons,
+    /// The desktop entries were rescanned, so slots that found no app may now
+    EntriesChanged,
+    SetSlot(Direction, AppQuery),
+    /// Asks for the subslot report as JSON, answered on the given channel
+    DumpSubslots(async_channel::Sender<String>),
+    /// Asks for the config in effect as TOML, answered on the given channel
+    DumpConfig(async_channel::Sender<String>),
+    /// Windows opened, closed or moved while the menu is open
+    ClientsChanged(Vec<ActiveClient>),
+}
+
+/// Forwards events from the background services to the GUI, collapsing bursts on the way so
+/// a flood never blocks the senders:
+/// - `ConfigReload`, `RefreshIcons` and `EntriesChanged` are dropped while one is already
+///   waiting
+/// - `CursorMove` and `ClientsChanged` replace a waiting one, so only the latest counts
+/// - `Show`, `Switch` and `Hide` are dropped when they repeat the last waiting event
+/// - anything else waits its turn, unless [`MAX_PENDING`] events already are
+pub async fn coalesce(rx: Receiver<AppEvent>, tx: Sender<AppEvent>) {
+    let mut pending = VecDeque::new();
+    let mut open = true;
+    loop {
+        if !open && pending.is_empty() {
+            return;
+        }
+        let next = pending.front().cloned();
+        let send_next = async {
+            match next {
+                Some(event) => tx.send(event).await.is_ok(),
+                None => std::future::pending().await,
+            }
+        };
+        tokio::select! {
+            biased;
+            event = rx.recv(), if open => match event {
+                Ok(event) => push_coalesced(&mut pending, event),
+                Err(_) => open = false,
+            },
+            sent = send_next => {
+                if !sent {
+                    return;
+                }
+                pending.pop_front();
+            }
+        }
+    }
+}
+
+fn push_coalesced(pending: &mut VecDeque<AppEvent>, event: AppEvent) {
+    let kind = discriminant(&event);
+    let same_kind = |e: &AppEvent| discriminant(e) == kind;
+    match event {
+        AppEvent::ConfigReload | AppEvent::RefreshIcons | AppEvent::EntriesChanged
+            if pending.iter().any(same_kind) =>
+        {
+            return;
+        }
+        AppEvent::CursorMove(_) | AppEvent::ClientsChanged(_) => {
+            if let Some(waiting) = pending.iter_mut().find(|e| same_kind(e)) {
+                *waiting = event;
+                return;
+            }
+        }
+        AppEvent::Show | AppEvent::Switch | AppEvent::Hide
+            if pending.back().is_some_and(same_kind) =>
+        {
+            return;
+        }
+        _ => {}
+    }
+    if pending.len() >= MAX_PENDING {
+        log::warn!("Too many pending events, dropping {:?}", event);
+        return;
+    }
+    pending.push_back(event);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bursts_collapse() {
+        let mut pending = VecDeque::new();
+        for event in [
+            AppEvent::ConfigReload,
+            AppEvent::CursorMove(Point::new(1.0, 1.0)),
+            AppEvent::Show,
+            AppEvent::Show,
+            AppEvent::ConfigReload,
+            AppEvent::CursorMove(Point::new(2.0, 2.0)),
+            AppEvent::Hide,
+            AppEvent::Show,
+        ] {
+            push_coalesced(&mut pending, event);
+        }
+        assert!(matches!(
+            Vec::from(pending).as_slice(),
+            [
+                AppEvent::ConfigReload,
+                AppEvent::CursorMove(p),
+                AppEvent::Show,
+                AppEvent::Hide,
+                AppEvent::Show,
+            ] if *p == Point::new(2.0, 2.0)
+        ));
+
+        let mut pending = VecDeque::new();
+        for i in 0..MAX_PENDING + 5 {
+            push_coalesced(&mut pending, AppEvent::Click(i as u32, Point::default()));
+        }
+        assert_eq!(pending.len(), MAX_PENDING);
+    }
+
+    #[tokio::test]
+    async fn test_coalesce_forwards_in_order() {
+        let (in_tx, in_rx) = async_channel::bounded(MAX_PENDING);
+        let (out_tx, out_rx) = async_channel::unbounded();
+        for _ in 0..10 {
+            in_tx.send(AppEvent::ConfigReload).await.unwrap();
+        }
+        in_tx.send(AppEvent::Hide).await.unwrap();
+        drop(in_tx);
+
+        coalesce(in_rx, out_tx).await;
+        let forwarded: Vec<_> = std::iter::from_fn(|| out_rx.try_recv().ok()).collect();
+        // everything was already queued, so the reloads collapse before any is forwarded
+        assert!(matches!(
+            forwarded.as_slice(),
+            [AppEvent::ConfigReload, AppEvent::Hide]
+        ));
+    }
 }
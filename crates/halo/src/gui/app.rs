@@ -1,14 +1,25 @@
-use crate::config;
-use crate::events::AppEvent;
-use crate::gui::menu::{self, SUB_KEYS, State};
+use crate::config::{self, CenterContent, Config, Direction, InputMode, SlotAction, SlotConfig};
+use crate::events::{AppEvent, Placement};
+use crate::gui::confine::PointerConstraints;
+use crate::gui::menu::{
+    self, Band, CLOSE_DURATION_MS, FLASH_DURATION_MS, INPUT_REGION_PADDING, IconData, SLOT_COUNT,
+    Slot, State,
+};
 use crate::gui::theme::{self, ThemeColors};
 use crate::gui::window;
+use crate::sys::broadcast::{self, MenuEvent};
+use crate::sys::usage::UsageStats;
+use crate::sys::{hooks, runtime};
 use gtk::prelude::*;
 use gtk4 as gtk;
-use hypraise::wm::{self, Point, ShellCommand};
+use hypraise::desktop::{self, AppInfo, AppQuery, Exec};
+use hypraise::icon;
+use hypraise::wm::{self, ActiveClient, Address, MonitorRect, Point, WindowClass};
 use relm4::prelude::*;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::path::PathBuf;
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 
 pub struct AppModel {
     pub state: Rc<RefCell<State>>,
@@ -16,26 +27,132 @@ pub struct AppModel {
     pub config_tx: async_channel::Sender<AppEvent>,
     pub root: gtk::ApplicationWindow,
     pub drawing_area: gtk::DrawingArea,
+    spinner_running: Rc<Cell<bool>>,
+    /// A batch of icons is being decoded, see `preload_icons`
+    icons_in_flight: bool,
+    clock_running: Rc<Cell<bool>>,
+    /// The monitor the pointer is kept on while the menu is open, see `confine_pointer`
+    confined_to: Rc<Cell<Option<MonitorRect>>>,
+    /// Bound when the compositor offers them; `confined_to` is the fallback without
+    pointer_constraints: Rc<RefCell<Option<PointerConstraints>>>,
+    /// The last slot activation, so a quick repeat of the same app can be ignored
+    last_activation: Option<(Instant, ActivationKey)>,
+    usage: UsageStats,
+    /// The window focused when the menu opened, to return to after closing another
+    prior_focus: Option<ActiveClient>,
+    /// The window a right-click on a slot last cycled to while the menu is open
+    cycled_to: Option<Address>,
+    /// The hover line last sent to event listeners, so only changes go out
+    last_hover_event: String,
+    /// The config in effect, `set-slot` overrides included, for `dump-config`
+    config: Config,
+}
+
+/// What tells a repeat activation apart: the app's class, or the slot for an app without
+/// one, like an `open` slot.
+#[derive(Debug, PartialEq)]
+enum ActivationKey {
+    Class(WindowClass),
+    Slot(usize),
+}
+
+/// What a show puts in the ring.
+#[derive(Debug)]
+enum ShowMode {
+    Slots,
+    /// Only the slots whose app is running
+    RunningOnly,
+    /// Quick switch: every open window
+    Switch,
+    /// The apps of a desktop entry category
+    Category(String),
 }
 
 #[derive(Debug)]
 pub enum AppMsg {
     Show,
+    ShowAt(Placement),
+    ShowRunning,
+    ShowCategory(String),
+    Switch,
+    Cancel,
     Hide,
-    Click(u32),
+    CycleSwitch,
+    ModifierRelease,
+    ToggleLabels,
+    /// Delete pressed: close the hovered window, as right-click does
+    CloseHovered,
+    Click(u32, Point),
+    /// Shift+click: move the window that had focus to the clicked slot's workspace
+    MoveFocused(Point),
     KeyPress(char),
+    /// A digit key pressed for the slot at this direction index
+    SlotKey(usize),
+    /// An arrow key pressed, moving the keyboard selection clockwise or back
+    MoveKeyboardFocus(bool),
+    /// Enter pressed: activate the keyboard selection
+    ActivateKeyboardFocus,
     CursorMove(Point),
     ConfigReload,
+    RefreshIcons,
+    /// The entry cache is rebuilt off the GTK thread; only the pixbufs are left to reload
+    IconsRefreshed,
+    /// Icons decoded in the background for the slots waiting on them, by file
+    IconsLoaded(Vec<(PathBuf, Option<IconData>)>),
+    EntriesChanged,
+    SetSlot(Direction, AppQuery),
+    DumpSubslots(async_channel::Sender<String>),
+    DumpConfig(async_channel::Sender<String>),
+    /// The activation pulse that started at this instant is over
+    FlashDone(Instant),
+    /// The close animation that started at this instant is over
+    CloseDone(Instant),
+    ClientsChanged(Vec<ActiveClient>),
+    /// The window stopped being the active one, e.g. after an alt-tab
+    FocusLost,
+}
+
+impl AppMsg {
+    /// Input aimed at the open menu, which a closing one ignores.
+    fn is_menu_input(&self) -> bool {
+        matches!(
+            self,
+            Self::Cancel
+                | Self::Hide
+                | Self::CycleSwitch
+                | Self::ModifierRelease
+                | Self::ToggleLabels
+                | Self::CloseHovered
+                | Self::Click(..)
+                | Self::MoveFocused(_)
+                | Self::KeyPress(_)
+                | Self::SlotKey(_)
+                | Self::MoveKeyboardFocus(_)
+                | Self::ActivateKeyboardFocus
+                | Self::CursorMove(_)
+                | Self::FocusLost
+        )
+    }
 }
 
 impl From<AppEvent> for AppMsg {
     fn from(event: AppEvent) -> Self {
         match event {
             AppEvent::Show => AppMsg::Show,
+            AppEvent::ShowAt(placement) => AppMsg::ShowAt(placement),
+            AppEvent::ShowRunning => AppMsg::ShowRunning,
+            AppEvent::ShowCategory(c) => AppMsg::ShowCategory(c),
+            AppEvent::Switch => AppMsg::Switch,
             AppEvent::Hide => AppMsg::Hide,
-            AppEvent::Click(b) => AppMsg::Click(b),
+            AppEvent::Click(b, p) => AppMsg::Click(b, p),
             AppEvent::CursorMove(p) => AppMsg::CursorMove(p),
             AppEvent::ConfigReload => AppMsg::ConfigReload,
+            AppEvent::RefreshIcons => AppMsg::RefreshIcons,
+            AppEvent::EntriesChanged => AppMsg::EntriesChanged,
+            AppEvent::SetSlot(d, q) => AppMsg::SetSlot(d, q),
+            AppEvent::DumpSubslots(reply) => AppMsg::DumpSubslots(reply),
+            AppEvent::DumpConfig(reply) => AppMsg::DumpConfig(reply),
+            AppEvent::ClientsChanged(clients) => AppMsg::ClientsChanged(clients),
         }
     }
 }
@@ -44,6 +161,7 @@ impl From<AppEvent> for AppMsg {
 impl SimpleComponent for AppModel {
     type Init = (
         State,
+        Config,
         async_channel::Sender<AppEvent>,
         async_channel::Receiver<AppEvent>,
     );
@@ -58,20 +176,54 @@ impl SimpleComponent for AppModel {
             #[watch]
             set_visible: model.visible,
             #[watch]
-            set_opacity: if model.visible { 1.0 } else { 0.0 },
+            set_opacity: model.opacity(),
             add_css_class: "halo-window",
             set_decorated: false,
 
             add_controller = gtk::EventControllerKey {
                 connect_key_pressed[sender] => move |_, key, _, _| {
                     if key == gtk::gdk::Key::Escape {
-                        sender.input(AppMsg::Hide);
+                        sender.input(AppMsg::Cancel);
+                        return glib::Propagation::Stop;
+                    } else if key == gtk::gdk::Key::Tab {
+                        sender.input(AppMsg::CycleSwitch);
+                        return glib::Propagation::Stop;
+                    } else if key == gtk::gdk::Key::slash {
+                        sender.input(AppMsg::ToggleLabels);
+                        return glib::Propagation::Stop;
+                    } else if key == gtk::gdk::Key::Delete {
+                        sender.input(AppMsg::CloseHovered);
+                        return glib::Propagation::Stop;
+                    } else if matches!(key, gtk::gdk::Key::Right | gtk::gdk::Key::Down) {
+                        sender.input(AppMsg::MoveKeyboardFocus(true));
+                        return glib::Propagation::Stop;
+                    } else if matches!(key, gtk::gdk::Key::Left | gtk::gdk::Key::Up) {
+                        sender.input(AppMsg::MoveKeyboardFocus(false));
+                        return glib::Propagation::Stop;
+                    } else if matches!(key, gtk::gdk::Key::Return | gtk::gdk::Key::KP_Enter) {
+                        sender.input(AppMsg::ActivateKeyboardFocus);
+                        return glib::Propagation::Stop;
+                    } else if let Some(d) = key.to_unicode().and_then(|c| c.to_digit(10))
+                        && (1..=SLOT_COUNT as u32).contains(&d) {
+                        // 1-8 follow the direction indices, starting at North
+                        sender.input(AppMsg::SlotKey(d as usize - 1));
                         return glib::Propagation::Stop;
                     } else if let Some(c) = key.to_unicode()
-                        && SUB_KEYS.contains(&c) {
+                        && !c.is_control() {
+                        // only the configured subslot keys do anything
                         sender.input(AppMsg::KeyPress(c))
                     }
                     glib::Propagation::Proceed
+                },
+                connect_key_released[sender] => move |_, key, _, _| {
+                    use gtk::gdk::Key;
+                    if matches!(
+                        key,
+                        Key::Alt_L | Key::Alt_R | Key::Super_L | Key::Super_R
+                            | Key::Control_L | Key::Control_R
+                    ) {
+                        sender.input(AppMsg::ModifierRelease);
+                    }
                 }
             },
 
@@ -91,8 +243,16 @@ impl SimpleComponent for AppModel {
 
                     add_controller = gtk::GestureClick {
                         set_button: 0, // Listen to all buttons
-                        connect_released[sender] => move |gesture, _, _, _| {
-                            sender.input(AppMsg::Click(gesture.current_button()));
+                        connect_released[sender] => move |gesture, _, x, y| {
+                            let point = Point::new(x, y);
+                            let shift = gesture
+                                .current_event_state()
+                                .contains(gtk::gdk::ModifierType::SHIFT_MASK);
+                            if shift && gesture.current_button() == 1 {
+                                sender.input(AppMsg::MoveFocused(point));
+                            } else {
+                                sender.input(AppMsg::Click(gesture.current_button(), point));
+                            }
                         }
                     }
                 }
@@ -105,19 +265,39 @@ impl SimpleComponent for AppModel {
         root: Self::Root,
         sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
-        let (state, config_tx, rx) = init;
+        let (state, config, config_tx, rx) = init;
 
         theme::load_css();
         window::init_layer_shell(&root);
 
         let state = Rc::new(RefCell::new(state));
 
+        // follow the accessibility setting live, since it can be toggled while halo runs
+        if let Some(settings) = gtk::Settings::default() {
+            state.borrow_mut().animations_enabled = settings.is_gtk_enable_animations();
+            let state = state.clone();
+            settings.connect_gtk_enable_animations_notify(move |settings| {
+                state.borrow_mut().animations_enabled = settings.is_gtk_enable_animations();
+            });
+        }
+
         let model = AppModel {
             state: state.clone(),
             visible: false,
             config_tx,
             root: root.clone(),
             drawing_area: gtk::DrawingArea::default(),
+            spinner_running: Rc::new(Cell::new(false)),
+            icons_in_flight: false,
+            clock_running: Rc::new(Cell::new(false)),
+            confined_to: Rc::new(Cell::new(None)),
+            pointer_constraints: Rc::new(RefCell::new(PointerConstraints::bind())),
+            last_activation: None,
+            usage: UsageStats::load(),
+            prior_focus: None,
+            cycled_to: None,
+            last_hover_event: String::new(),
+            config,
         };
 
         let widgets = view_output!();
@@ -128,10 +308,19 @@ impl SimpleComponent for AppModel {
         let state_draw = model.state.clone();
         widgets
             .drawing_area
-            .set_draw_func(move |drawing_area, cr, _, _| {
+            .set_draw_func(move |drawing_area, cr, width, height| {
                 let style_context = drawing_area.style_context();
-                let colors = ThemeColors::from_context(&style_context);
-                if let Err(e) = menu::draw(cr, &state_draw.borrow(), &colors) {
+                let (width, height) = (width as f64, height as f64);
+                if state_draw.borrow_mut().clamp_center(width, height)
+                    && let Some(window) =
+                        drawing_area.root().and_downcast::<gtk::ApplicationWindow>()
+                {
+                    set_input_region(&window, &state_draw.borrow());
+                }
+                let state = state_draw.borrow();
+                let colors =
+                    ThemeColors::from_context(&style_context).with_overrides(&state.colors);
+                if let Err(e) = menu::draw(cr, &state, &colors, width, height) {
                     log::error!("Drawing error: {}", e);
                 }
             });
@@ -143,85 +332,237 @@ impl SimpleComponent for AppModel {
             }
         });
 
+        // icons resolve against the GTK icon theme, and follow it when it changes
+        if let Some(settings) = gtk::Settings::default() {
+            let theme = settings.gtk_icon_theme_name().map(|t| t.to_string());
+            if theme != icon::icon_theme() {
+                icon::set_icon_theme(theme);
+                sender.input(AppMsg::RefreshIcons);
+            }
+            let sender = sender.clone();
+            settings.connect_gtk_icon_theme_name_notify(move |settings| {
+                icon::set_icon_theme(settings.gtk_icon_theme_name().map(|t| t.to_string()));
+                sender.input(AppMsg::RefreshIcons);
+            });
+        }
+
+        // poll for window changes only while there's a menu to keep current
+        let state_map = model.state.clone();
+        root.connect_map(move |_| {
+            runtime::set_client_polling(state_map.borrow().live_client_updates);
+        });
+        let constraints = model.pointer_constraints.clone();
+        root.connect_unmap(move |_| {
+            runtime::set_client_polling(false);
+            if let Some(constraints) = constraints.borrow_mut().as_mut() {
+                constraints.release();
+            }
+        });
+
+        let sender_focus = sender.clone();
+        root.connect_is_active_notify(move |window| {
+            if !window.is_active() {
+                sender_focus.input(AppMsg::FocusLost);
+            }
+        });
+
         root.set_visible(false);
+        model.preload_icons(&sender);
 
         ComponentParts { model, widgets }
     }
 
-    fn update(&mut self, msg: Self::Input, _sender: ComponentSender<Self>) {
-        match msg {
-            AppMsg::Show => {
-                let monitor_name = wm::get_active_monitor();
-                let mut monitor_height = 1440.0;
-                if let Some(name) = &monitor_name {
-                    window::set_window_monitor(&self.root, name);
-                    if let Some(m) = window::get_monitor_by_name(name) {
-                        monitor_height = m.geometry().height() as f64;
-                    }
-                }
-
-                self.visible = true;
+    fn update(&mut self, msg: Self::Input, sender: ComponentSender<Self>) {
+        if msg.is_menu_input() && self.closing() {
+            return;
+        }
+        self.handle(msg, sender.clone());
+        self.preload_icons(&sender);
+        if broadcast::is_enabled() {
+            self.broadcast_hover();
+        }
+    }
+}
 
-                let cursor_pos = window::get_cursor_position(&self.root)
-                    .or_else(wm::get_cursor_pos_on_active_monitor)
-                    .unwrap_or_default();
+/// Takes pointer input only over the ring with `limit_input_region`, wherever it's centered
+/// and however wide it is now.
+fn set_input_region(root: &gtk::ApplicationWindow, state: &State) {
+    if state.limit_input_region {
+        let padding = INPUT_REGION_PADDING * state.scale_factor;
+        window::set_input_circle(root, state.center, state.ring_radius() + padding);
+    } else {
+        window::clear_input_region(root);
+    }
+}
 
-                let classes = wm::get_active_classes();
-                self.state
-                    .borrow_mut()
-                    .refresh(cursor_pos, classes, monitor_height);
-                self.drawing_area.queue_draw();
+impl AppModel {
+    fn handle(&mut self, msg: AppMsg, sender: ComponentSender<Self>) {
+        match msg {
+            AppMsg::Show => self.show(ShowMode::Slots, Placement::default()),
+            AppMsg::ShowAt(placement) => self.show(ShowMode::Slots, placement),
+            AppMsg::ShowRunning => self.show(ShowMode::RunningOnly, Placement::default()),
+            AppMsg::ShowCategory(category) => {
+                self.show(ShowMode::Category(category), Placement::default())
+            }
+            AppMsg::Switch => self.show(ShowMode::Switch, Placement::default()),
+            AppMsg::Cancel => {
+                if self.visible && self.state.borrow_mut().leave_submenu() {
+                    self.update_input_region();
+                    self.drawing_area.queue_draw();
+                } else {
+                    self.hide(&sender);
+                }
             }
             AppMsg::Hide => {
-                self.visible = false;
+                // in quick-switch mode, releasing the hold key commits the selection
+                if self.visible && self.state.borrow().switch_mode {
+                    self.focus_hovered_subslot();
+                }
+                self.hide(&sender);
             }
-            AppMsg::Click(btn) => {
+            AppMsg::CycleSwitch => {
                 if !self.visible {
                     return;
                 }
-                if btn == 3 {
+                let mut state = self.state.borrow_mut();
+                // outside quick switch, Tab flips between running apps and every slot
+                if state.switch_mode {
+                    state.cycle_subslot();
+                } else if !state.in_category_ring() {
+                    state.toggle_running_only();
+                }
+                self.drawing_area.queue_draw();
+            }
+            AppMsg::ModifierRelease => {
+                if self.visible && self.state.borrow().switch_mode {
+                    self.focus_hovered_subslot();
+                    self.hide(&sender);
+                }
+            }
+            AppMsg::ToggleLabels => {
+                if self.visible {
+                    let mut state = self.state.borrow_mut();
+                    state.labels_visible = !state.labels_visible;
+                    self.drawing_area.queue_draw();
+                }
+            }
+            AppMsg::Click(btn, point) => {
+                if !self.visible || self.flashing() {
+                    return;
+                }
+                let (touch, center) = {
                     let state = self.state.borrow();
-
-                    let _ = state
-                        .hover_index
-                        .and_then(|i| state.slots.get(i))
-                        .filter(|s| s.is_running(&state.active_classes))
-                        .and_then(|s| s.app.as_ref())
-                        .map(|app| {
-                            if let Err(e) = wm::close_window(&app.class) {
-                                log::error!("Failed to close window: {}", e);
-                            }
-                        });
+                    (
+                        state.input_mode == InputMode::Touch && !state.switch_mode,
+                        state.center,
+                    )
+                };
+                let subslot = {
+                    let state = self.state.borrow();
+                    (btn != 3 && !state.switch_mode)
+                        .then(|| state.subslot_at(point))
+                        .flatten()
+                };
+                if let Some(index) = subslot {
+                    self.state.borrow_mut().select_subslot(index);
+                    self.focus_hovered_subslot();
+                    self.hide(&sender);
+                    return;
                 }
-                self.visible = false;
+                if btn != 3 && self.state.borrow().in_dead_zone(point) {
+                    // the center goes back out of a submenu
+                    if self.state.borrow_mut().leave_submenu() {
+                        self.update_input_region();
+                        self.drawing_area.queue_draw();
+                        return;
+                    }
+                }
+                if touch && btn != 3 {
+                    // a tap doesn't leave the ring, so the submenu keeps its center
+                    if self.state.borrow_mut().select_at(point).is_some()
+                        && !self.activate_hovered(center, SlotAction::RunOrRaise, Some(point))
+                    {
+                        self.drawing_area.queue_draw();
+                        return;
+                    }
+                    self.hide_after_flash(&sender);
+                    return;
+                }
+                if btn == 3 {
+                    if self.cycle_hovered_windows() {
+                        return;
+                    }
+                    self.close_hovered();
+                } else {
+                    // past the outer radius a click does what crossing there does
+                    let band = {
+                        let mut state = self.state.borrow_mut();
+                        let enabled = state.activation.click_activates && !state.switch_mode;
+                        enabled.then(|| state.hover_for_click(point)).flatten()
+                    };
+                    if let Some(band) = band {
+                        self.activate_band(point, band, &sender);
+                        return;
+                    }
+                }
+                self.hide(&sender);
+            }
+            AppMsg::MoveFocused(point) => {
+                if !self.visible || self.flashing() || self.state.borrow().switch_mode {
+                    return;
+                }
+                self.move_focused_to(point);
+                self.hide(&sender);
+            }
+            AppMsg::CloseHovered => {
+                if !self.visible || self.flashing() {
+                    return;
+                }
+                self.close_hovered();
+                self.hide(&sender);
             }
             AppMsg::KeyPress(c) => {
                 let state = self.state.borrow();
+                if !state.sub_keys.contains(&c) {
+                    return;
+                }
                 if let Some(app) = state.subslots.iter().find(|s| s.key == c).or(None) {
                     wm::focus_window(&app.client.address)
                         .unwrap_or_else(|e| log::error!("Failed to focus app: {}", e))
                 }
-                self.visible = false;
+                drop(state);
+                self.hide(&sender);
+            }
+            AppMsg::SlotKey(index) => {
+                if !self.visible || self.flashing() || self.state.borrow().switch_mode {
+                    return;
+                }
+                self.activate_index(index, &sender);
+            }
+            AppMsg::MoveKeyboardFocus(clockwise) => {
+                if !self.visible || self.flashing() || self.state.borrow().switch_mode {
+                    return;
+                }
+                if self.state.borrow_mut().move_keyboard_focus(clockwise) {
+                    self.drawing_area.queue_draw();
+                }
+            }
+            AppMsg::ActivateKeyboardFocus => {
+                if !self.visible || self.flashing() || self.state.borrow().switch_mode {
+                    return;
+                }
+                let index = self.state.borrow().keyboard_index;
+                if let Some(index) = index {
+                    self.activate_index(index, &sender);
+                }
             }
             AppMsg::CursorMove(point) => {
-                if !self.visible {
+                if !self.visible || self.flashing() {
                     return;
                 }
                 let action = self.state.borrow_mut().update_cursor(point);
-                if action.should_activate
-                    && let Some(app_info) = self.state.borrow().get_hovered_app()
-                {
-                    if app_info.exec.as_str() == "HALO_SETUP" {
-                        if let Ok(path) = config::write_default_config() {
-                            let _ = std::process::Command::new("xdg-open").arg(&path).spawn();
-                        }
-                    } else if let Err(e) = wm::run_or_raise(
-                        &app_info.class,
-                        &ShellCommand::from(app_info.exec.to_string()),
-                    ) {
-                        log::error!("Failed to run or raise '{}': {}", app_info.name, e);
-                    }
-                    self.visible = false;
+                if action.should_activate && self.state.borrow().get_hovered_slot().is_some() {
+                    self.activate_band(point, action.band, &sender);
                 }
                 if action.should_redraw {
                     self.drawing_area.queue_draw();
@@ -229,14 +570,674 @@ impl SimpleComponent for AppModel {
             }
             AppMsg::ConfigReload => match config::load_config() {
                 Ok(new_config) => {
-                    let new_slots = State::init_slots(&new_config);
-                    self.state.borrow_mut().slots = new_slots;
-                    self.state.borrow_mut().show_subslots = new_config.show_active_clients;
+                    let rebuilt = self.state.borrow_mut().update_slots(&new_config.slots);
+                    self.state.borrow_mut().apply_config(&new_config);
+                    self.config = new_config;
                     self.drawing_area.queue_draw();
-                    log::info!("Configuration reloaded");
+                    log::info!("Configuration reloaded, {} slot(s) changed", rebuilt);
                 }
                 Err(e) => log::error!("Failed to reload config: {}", e),
             },
+            AppMsg::SetSlot(direction, query) => {
+                let slot_config = SlotConfig {
+                    direction: Some(direction),
+                    app: Some(query),
+                    ..Default::default()
+                };
+                let slot = Slot::from_config(&slot_config);
+                self.state.borrow_mut().set_slot(direction, slot);
+                self.config
+                    .slots
+                    .retain(|cfg| cfg.direction != Some(direction));
+                self.config.slots.push(slot_config);
+                self.drawing_area.queue_draw();
+                log::info!("Slot {} overridden until the next config reload", direction);
+            }
+            AppMsg::DumpSubslots(reply) => {
+                let report = self.state.borrow().subslot_report();
+                let json = serde_json::to_string(&report).unwrap_or_else(|e| {
+                    log::error!("Failed to serialize subslot report: {}", e);
+                    format!("err: {e}")
+                });
+                let _ = reply.try_send(json);
+            }
+            AppMsg::DumpConfig(reply) => {
+                let toml = toml::to_string(&self.config).unwrap_or_else(|e| {
+                    log::error!("Failed to serialize config: {}", e);
+                    format!("err: {e}")
+                });
+                let _ = reply.try_send(toml);
+            }
+            AppMsg::FlashDone(started) => {
+                // a newer show or activation has taken over since
+                let done = self
+                    .state
+                    .borrow()
+                    .flash
+                    .is_some_and(|(_, at)| at == started);
+                if done {
+                    self.state.borrow_mut().flash = None;
+                    self.hide(&sender);
+                }
+            }
+            AppMsg::CloseDone(started) => {
+                // a show since has called the close off
+                let mut state = self.state.borrow_mut();
+                if state.closing_at == Some(started) {
+                    state.closing_at = None;
+                    self.visible = false;
+                }
+            }
+            AppMsg::ClientsChanged(clients) => {
+                if self.visible && !self.flashing() {
+                    self.state.borrow_mut().update_clients(clients);
+                    self.update_input_region();
+                    if self.state.borrow().is_pulsing() {
+                        self.start_spinner();
+                    }
+                    self.drawing_area.queue_draw();
+                }
+            }
+            AppMsg::FocusLost => {
+                if !self.visible || self.flashing() {
+                    return;
+                }
+                if self.state.borrow().hide_on_focus_loss {
+                    self.hide(&sender);
+                } else if self.state.borrow_mut().reset_hover() {
+                    self.drawing_area.queue_draw();
+                }
+            }
+            AppMsg::RefreshIcons => {
+                // the entry cache holds resolved paths too, so subslots need a rescan
+                let sender = sender.clone();
+                relm4::spawn(async move {
+                    let _ = tokio::task::spawn_blocking(desktop::refresh_cache).await;
+                    sender.input(AppMsg::IconsRefreshed);
+                });
+            }
+            AppMsg::IconsLoaded(icons) => {
+                self.icons_in_flight = false;
+                self.state.borrow_mut().set_icons(icons);
+                self.drawing_area.queue_draw();
+            }
+            AppMsg::IconsRefreshed => {
+                self.state.borrow_mut().reload_icons();
+                self.drawing_area.queue_draw();
+                log::info!("Icons reloaded");
+            }
+            AppMsg::EntriesChanged => {
+                let rebuilt = self.state.borrow_mut().rebuild_broken_slots();
+                self.drawing_area.queue_draw();
+                log::info!(
+                    "Desktop entries rescanned, {} slot(s) resolved again",
+                    rebuilt
+                );
+            }
+        }
+    }
+
+    /// Tells event listeners about a hover change since the last message, whatever caused it.
+    fn broadcast_hover(&mut self) {
+        let line = {
+            let state = self.state.borrow();
+            let hovered = state
+                .hover_index
+                .zip(state.get_hovered_app())
+                .filter(|_| self.visible);
+            MenuEvent::Hover(hovered.map(|(i, app)| (state.slot_direction(i), app.name.as_str())))
+                .to_string()
+        };
+        if line != self.last_hover_event {
+            broadcast::publish(&line);
+            self.last_hover_event = line;
+        }
+    }
+
+    fn show(&mut self, mode: ShowMode, placement: Placement) {
+        let monitor_name = wm::get_active_monitor();
+        let mut monitor_height = 1440.0;
+        let mut monitor_width = None;
+        if let Some(name) = &monitor_name {
+            window::set_window_monitor(&self.root, name);
+            if let Some(m) = window::get_monitor_by_name(name) {
+                // refresh keeps the last scale for a height of 0, as during hotplug
+                monitor_height = m.geometry().height() as f64;
+                monitor_width = Some(m.geometry().width() as f64);
+            }
+        }
+
+        self.visible = true;
+
+        let cursor_pos = placement
+            .at
+            .or_else(|| window::get_cursor_position(&self.root))
+            .or_else(wm::get_cursor_pos_on_active_monitor)
+            .unwrap_or_default();
+
+        {
+            let mut state = self.state.borrow_mut();
+            let ignore_special = state.ignore_special_workspaces;
+            let clients = wm::get_active_clients();
+            self.prior_focus = clients.iter().find(|c| c.focused).cloned();
+            self.cycled_to = None;
+            // slots the last show's context swapped in go back unless this one wants them too
+            let context_slots = self.config.slots_for(self.prior_focus.as_ref());
+            if state.update_slots(&context_slots) > 0 {
+                log::debug!("Slots changed for the focused window's context");
+            }
+            state.set_urgent_clients(&clients);
+            runtime::set_polled_clients(&clients);
+            let classes = clients
+                .iter()
+                .filter(|c| !(ignore_special && c.is_on_special_workspace()))
+                .flat_map(|c| c.match_classes().cloned())
+                .collect();
+            state.running_only = match mode {
+                ShowMode::Slots => state.running_only_by_default,
+                ShowMode::RunningOnly => true,
+                ShowMode::Switch | ShowMode::Category(_) => false,
+            };
+            state.switch_mode = matches!(mode, ShowMode::Switch);
+            state.category = match mode {
+                ShowMode::Category(category) => Some(category),
+                _ => None,
+            };
+            if state.weight_by_usage {
+                state.usage_counts = self.usage.counts().clone();
+            }
+            state.refresh(cursor_pos, classes, monitor_height);
+            state.set_slot_windows(&clients);
+            if let Some(scale) = placement.scale {
+                state.rescale(scale);
+            }
+            // an anchor off the monitor would put the ring where nothing can reach it
+            if placement.at.is_some()
+                && let Some(width) = monitor_width
+                && state.clamp_center(width, monitor_height)
+            {
+                log::debug!(
+                    "Moved the ring's anchor onto the monitor, to {:?}",
+                    state.center
+                );
+            }
+            if state.center_content == Some(CenterContent::Workspace) {
+                state.workspace_name = wm::get_active_workspace();
+            }
+            // re-read on every show, since binds change whenever Hyprland reloads its config
+            if state.show_keybind_hints {
+                state.set_keybind_hints(&wm::get_binds());
+            }
+        }
+
+        self.update_input_region();
+        let state = self.state.borrow();
+        if state.is_loading() || state.is_pulsing() {
+            self.start_spinner();
+        }
+        if state.fanning_out() {
+            self.start_fan_out();
+        }
+        if state.center_content == Some(CenterContent::Clock) {
+            self.start_clock();
+        }
+        if state.confine_pointer {
+            self.confine_pointer();
+        }
+        self.drawing_area.queue_draw();
+    }
+
+    fn update_input_region(&self) {
+        set_input_region(&self.root, &self.state.borrow());
+    }
+
+    /// Decodes the icons slots are waiting for off the GTK thread, a batch at a time. Slots
+    /// built while one is out are picked up by the next.
+    fn preload_icons(&mut self, sender: &ComponentSender<Self>) {
+        if self.icons_in_flight {
+            return;
+        }
+        let paths = self.state.borrow().pending_icons();
+        if paths.is_empty() {
+            return;
+        }
+        self.icons_in_flight = true;
+        let sender = sender.clone();
+        relm4::spawn(async move {
+            let icons = tokio::task::spawn_blocking(move || {
+                paths
+                    .into_iter()
+                    .map(|path| {
+                        let icon = IconData::load(&path);
+                        (path, icon)
+                    })
+                    .collect()
+            })
+            .await
+            .unwrap_or_default();
+            sender.input(AppMsg::IconsLoaded(icons));
+        });
+    }
+
+    /// Keeps redrawing while any slot's icon is still loading so the spinners turn, or a
+    /// window wants attention so its slot pulses.
+    fn start_spinner(&self) {
+        if self.spinner_running.replace(true) {
+            return;
+        }
+        let state = self.state.clone();
+        let drawing_area = self.drawing_area.clone();
+        let running = self.spinner_running.clone();
+        glib::timeout_add_local(Duration::from_millis(50), move || {
+            drawing_area.queue_draw();
+            let animating = state.borrow().is_loading() || state.borrow().is_pulsing();
+            if animating && drawing_area.is_mapped() {
+                glib::ControlFlow::Continue
+            } else {
+                running.set(false);
+                glib::ControlFlow::Break
+            }
+        });
+    }
+
+    /// Redraws every frame until the subslots have fanned out into place.
+    fn start_fan_out(&self) {
+        let state = self.state.clone();
+        let drawing_area = self.drawing_area.clone();
+        glib::timeout_add_local(Duration::from_millis(16), move || {
+            drawing_area.queue_draw();
+            if state.borrow().fanning_out() && drawing_area.is_mapped() {
+                glib::ControlFlow::Continue
+            } else {
+                glib::ControlFlow::Break
+            }
+        });
+    }
+
+    /// Runs the slot at `index` as if it had been flicked, for the keys that pick a slot.
+    fn activate_index(&mut self, index: usize, sender: &ComponentSender<Self>) {
+        if self.state.borrow_mut().select_index(index).is_some() {
+            let center = self.state.borrow().center;
+            if self.activate_hovered(center, SlotAction::RunOrRaise, None) {
+                self.hide_after_flash(sender);
+            }
+            self.drawing_area.queue_draw();
+        }
+    }
+
+    /// Runs the hovered slot's action for reaching `band` at `point`, by a flick or a click.
+    fn activate_band(&mut self, point: Point, band: Band, sender: &ComponentSender<Self>) {
+        let (slot_action, last_band) = {
+            let activation = self.state.borrow().activation;
+            match band {
+                Band::Far => (activation.far_action, true),
+                _ => (activation.near_action, activation.far_radius.is_none()),
+            }
+        };
+        // short of the last band the menu stays up, so the flick can continue
+        if self.activate_hovered(point, slot_action, Some(point)) && last_band {
+            self.hide_after_flash(sender);
+        } else {
+            self.drawing_area.queue_draw();
+        }
+    }
+
+    fn flashing(&self) -> bool {
+        self.state.borrow().flash.is_some()
+    }
+
+    fn closing(&self) -> bool {
+        self.state.borrow().closing_at.is_some()
+    }
+
+    fn opacity(&self) -> f64 {
+        if self.visible {
+            1.0 - self.state.borrow().close_progress()
+        } else {
+            0.0
+        }
+    }
+
+    /// Hides the menu, fading and shrinking it away first when animations are on. It stays
+    /// `visible` and ignores input while it closes; a show in the meantime calls the close off.
+    fn hide(&mut self, sender: &ComponentSender<Self>) {
+        if !self.visible || self.closing() {
+            return;
+        }
+        if !self.state.borrow().animations_enabled {
+            self.visible = false;
+            return;
+        }
+        let started = Instant::now();
+        self.state.borrow_mut().closing_at = Some(started);
+
+        let state = self.state.clone();
+        let root = self.root.clone();
+        let drawing_area = self.drawing_area.clone();
+        let sender = sender.clone();
+        glib::timeout_add_local(Duration::from_millis(16), move || {
+            let state = state.borrow();
+            if state.closing_at != Some(started) {
+                return glib::ControlFlow::Break;
+            }
+            root.set_opacity(1.0 - state.close_progress());
+            drawing_area.queue_draw();
+            if started.elapsed() < Duration::from_millis(CLOSE_DURATION_MS) {
+                glib::ControlFlow::Continue
+            } else {
+                sender.input(AppMsg::CloseDone(started));
+                glib::ControlFlow::Break
+            }
+        });
+    }
+
+    /// Hides the menu, first letting a slot that just launched something finish its pulse.
+    fn hide_after_flash(&mut self, sender: &ComponentSender<Self>) {
+        let flash = self.state.borrow().flash;
+        let Some((_, started)) = flash else {
+            self.hide(sender);
+            return;
+        };
+        self.drawing_area.queue_draw();
+        let drawing_area = self.drawing_area.clone();
+        let sender = sender.clone();
+        glib::timeout_add_local(Duration::from_millis(16), move || {
+            drawing_area.queue_draw();
+            if started.elapsed() < Duration::from_millis(FLASH_DURATION_MS) {
+                glib::ControlFlow::Continue
+            } else {
+                sender.input(AppMsg::FlashDone(started));
+                glib::ControlFlow::Break
+            }
+        });
+    }
+
+    /// Redraws every second while the menu is up, so a center clock keeps time.
+    fn start_clock(&self) {
+        if self.clock_running.replace(true) {
+            return;
+        }
+        let drawing_area = self.drawing_area.clone();
+        let running = self.clock_running.clone();
+        glib::timeout_add_seconds_local(1, move || {
+            if drawing_area.is_mapped() {
+                drawing_area.queue_draw();
+                glib::ControlFlow::Continue
+            } else {
+                running.set(false);
+                glib::ControlFlow::Break
+            }
+        });
+    }
+
+    /// Keeps the pointer on the monitor the menu opened on until the window hides. The window
+    /// covers that monitor, so a pointer constraint on it does the job; otherwise, or when the
+    /// input region is only the ring, the pointer is warped back whenever it crosses to
+    /// another monitor. Does nothing if the monitor can't be found.
+    fn confine_pointer(&self) {
+        // the compositor confines to the input region too, which would trap it in the ring
+        if !self.state.borrow().limit_input_region
+            && let Some(constraints) = self.pointer_constraints.borrow_mut().as_mut()
+            && constraints.confine(&self.root)
+        {
+            return;
+        }
+        let Some(rect) = wm::get_active_monitor_rect() else {
+            return;
+        };
+        // a show on another monitor only moves the running confinement there
+        if self.confined_to.replace(Some(rect)).is_some() {
+            return;
+        }
+        let root = self.root.clone();
+        let confined_to = self.confined_to.clone();
+        glib::timeout_add_local(Duration::from_millis(16), move || match confined_to.get() {
+            Some(rect) if root.is_visible() => {
+                wm::confine_cursor(&rect);
+                glib::ControlFlow::Continue
+            }
+            _ => {
+                confined_to.set(None);
+                glib::ControlFlow::Break
+            }
+        });
+    }
+
+    /// Activates the hovered slot unless the same app was activated within the cooldown.
+    /// A group opens its submenu around `center` instead; returns `false` then, as the menu
+    /// stays open. A `pointer` on a half of the slot with its own exec launches that instead.
+    fn activate_hovered(
+        &mut self,
+        center: Point,
+        action: SlotAction,
+        pointer: Option<Point>,
+    ) -> bool {
+        let mut state = self.state.borrow_mut();
+        let Some(index) = state.hover_index else {
+            return true;
+        };
+        if state.enter_submenu(index, center) {
+            drop(state);
+            self.update_input_region();
+            return false;
+        }
+
+        let half = pointer.and_then(|p| state.hovered_half(p));
+        let Some(slot) = state.get_hovered_slot() else {
+            return true;
+        };
+        let half_exec = half.and_then(|h| slot.half_exec(h));
+        // raising can't start a second instance, so it neither waits for nor starts a cooldown
+        let cooldown = slot
+            .app
+            .as_ref()
+            .filter(|_| action != SlotAction::Raise)
+            .map(|app| {
+                let key = if app.class.is_empty() {
+                    ActivationKey::Slot(index)
+                } else {
+                    ActivationKey::Class(app.class.clone())
+                };
+                (app, key)
+            });
+        if let Some((app, key)) = &cooldown
+            && self
+                .last_activation
+                .as_ref()
+                .is_some_and(|(at, last)| last == key && at.elapsed() < state.activation_cooldown)
+        {
+            log::debug!("Ignoring repeat activation of '{}'", app.name);
+            return true;
+        }
+        // only a new window gets the pulse, since a raised one is feedback enough
+        let launches = slot.open.is_some()
+            || half_exec.is_some()
+            || match action {
+                SlotAction::Launch => !slot.single_instance,
+                // as of the menu's client list, rather than asking Hyprland again
+                SlotAction::RunOrRaise => !slot.is_running(&state.active_classes),
+                SlotAction::Raise => false,
+            };
+        let activated = Self::activate(slot, action, half_exec);
+        // a launch the pre_exec hook stopped can be retried straight away
+        if activated && let Some((app, key)) = cooldown {
+            if state.weight_by_usage && !app.class.is_empty() {
+                self.usage.record(&app.class);
+            }
+            self.last_activation = Some((Instant::now(), key));
+        }
+        if activated && let Some(app) = &slot.app {
+            broadcast::publish(MenuEvent::Activate(app.name.as_str()));
+        }
+        if activated && launches && state.animations_enabled {
+            state.flash = Some((index, Instant::now()));
+        }
+        true
+    }
+
+    /// Runs the slot's action, or launches `half_exec` in its place, between its hooks.
+    /// Returns whether it went ahead.
+    fn activate(slot: &Slot, action: SlotAction, half_exec: Option<&Exec>) -> bool {
+        let Some(app_info) = &slot.app else {
+            return false;
+        };
+        if app_info.exec.is_setup() {
+            config::open_setup();
+            return true;
+        }
+
+        let slot_config = slot.config.as_ref();
+        if let Some(pre_exec) = slot_config.and_then(|c| c.pre_exec.as_deref())
+            && let Err(e) = hooks::run_hook(pre_exec, hooks::HOOK_TIMEOUT)
+        {
+            log::error!(
+                "Not activating '{}', its pre_exec '{}' {}",
+                app_info.name,
+                pre_exec,
+                e
+            );
+            return false;
+        }
+
+        let activated = Self::run_action(slot, app_info, action, half_exec);
+        if activated && let Some(post_exec) = slot_config.and_then(|c| c.post_exec.as_deref()) {
+            hooks::spawn_hook(post_exec);
+        }
+        activated
+    }
+
+    fn run_action(
+        slot: &Slot,
+        app_info: &AppInfo,
+        action: SlotAction,
+        half_exec: Option<&Exec>,
+    ) -> bool {
+        if let Some(target) = &slot.open {
+            let mut xdg_open = std::process::Command::new("xdg-open");
+            if let Err(e) = wm::spawn_detached(xdg_open.arg(target)) {
+                log::error!("Failed to open '{}': {}", target, e);
+                return false;
+            }
+            return true;
+        }
+
+        let (class, exec, notify) = (&app_info.class, &app_info.exec, app_info.startup_notify);
+        let result = match action {
+            _ if let Some(exec) = half_exec => wm::launch_new(class, exec, notify),
+            SlotAction::RunOrRaise if slot.toggle => wm::toggle_app(class, exec, notify),
+            SlotAction::RunOrRaise | SlotAction::Launch if slot.single_instance => {
+                wm::run_single_instance(class, exec, notify)
+            }
+            SlotAction::RunOrRaise => wm::run_or_raise(class, exec, notify),
+            SlotAction::Raise => wm::raise(class).map(|_| ()).map_err(Into::into),
+            SlotAction::Launch => wm::launch_new(class, exec, notify),
+        };
+        if let Err(e) = &result {
+            log::error!("Failed to activate '{}': {}", app_info.name, e);
+        }
+        result.is_ok()
+    }
+
+    /// Sends the window that had focus before the menu opened to the workspace of the slot at
+    /// `point`: the slot's configured `workspace`, or else wherever its app's window is.
+    fn move_focused_to(&self, point: Point) {
+        let Some(window) = &self.prior_focus else {
+            return;
+        };
+        let mut state = self.state.borrow_mut();
+        let Some(slot) = state.select_at(point).and_then(|i| state.slots.get(i)) else {
+            return;
+        };
+        let workspace = slot
+            .config
+            .as_ref()
+            .and_then(|c| c.workspace.clone())
+            .or_else(|| {
+                slot.app
+                    .as_ref()
+                    .and_then(|app| wm::workspace_of(&app.class))
+            });
+        let Some(workspace) = workspace else {
+            log::info!("No workspace to move the window to: the app isn't open");
+            return;
+        };
+        if let Err(e) = wm::move_window_to_workspace(&window.address, &workspace) {
+            log::error!("Failed to move window to workspace '{}': {}", workspace, e);
+        }
+    }
+
+    /// Closes the window under the hover: the hovered subslot's own window, or else the
+    /// hovered slot's app if it's running.
+    fn close_hovered(&self) {
+        let state = self.state.borrow();
+
+        let closed = if let Some(subslot) = state.get_hovered_subslot() {
+            wm::close_window_by_address(&subslot.client.address)
+                .map(|()| subslot.client.class.clone())
+        } else {
+            let Some(app) = state
+                .hover_index
+                .and_then(|i| state.slots.get(i))
+                .filter(|s| s.is_running(&state.active_classes))
+                .and_then(|s| s.app.as_ref())
+            else {
+                return;
+            };
+            wm::close_window(&app.class).map(|()| app.class.clone())
+        };
+        match closed {
+            Ok(class) if state.return_focus_after_close => self.return_focus(&class),
+            Ok(_) => {}
+            Err(e) => log::error!("Failed to close window: {}", e),
+        }
+    }
+
+    /// Focuses the window from before the menu opened, if it's still open and wasn't one of
+    /// the windows of `closed`.
+    fn return_focus(&self, closed: &WindowClass) {
+        let Some(prior) = &self.prior_focus else {
+            return;
+        };
+        if prior.class.to_lowercase() == closed.to_lowercase() {
+            return;
+        }
+        if wm::get_active_clients()
+            .iter()
+            .any(|c| c.address == prior.address)
+        {
+            wm::focus_window(&prior.address)
+                .unwrap_or_else(|e| log::error!("Failed to return focus: {}", e));
+        }
+    }
+
+    /// Focuses the hovered slot's next window, after the one last cycled to or else the one
+    /// that had focus. Only slots with several windows cycle; returns whether this one did,
+    /// in which case the menu stays open for the next right-click.
+    fn cycle_hovered_windows(&mut self) -> bool {
+        let next = {
+            let state = self.state.borrow();
+            let Some(slot) = state.get_hovered_slot().filter(|s| s.windows.len() > 1) else {
+                return false;
+            };
+            let current = self
+                .cycled_to
+                .as_ref()
+                .or(self.prior_focus.as_ref().map(|c| &c.address));
+            slot.window_after(current).cloned()
+        };
+        if let Some(address) = next {
+            match wm::focus_window(&address) {
+                Ok(()) => self.cycled_to = Some(address),
+                Err(e) => log::error!("Failed to focus window: {}", e),
+            }
+        }
+        true
+    }
+
+    fn focus_hovered_subslot(&self) {
+        if let Some(subslot) = self.state.borrow().get_hovered_subslot() {
+            wm::focus_window(&subslot.client.address)
+                .unwrap_or_else(|e| log::error!("Failed to focus app: {}", e));
         }
     }
 }
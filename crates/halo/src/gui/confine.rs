@@ -0,0 +1,95 @@
+use gdk4_wayland::prelude::*;
+use gdk4_wayland::{WaylandDevice, WaylandDisplay, WaylandSurface};
+use gtk::gdk;
+use gtk::prelude::*;
+use gtk4 as gtk;
+use wayland_client::globals::{GlobalListContents, registry_queue_init};
+use wayland_client::protocol::wl_registry::WlRegistry;
+use wayland_client::{Connection, Dispatch, EventQueue, Proxy, QueueHandle, delegate_noop};
+use wayland_protocols::wp::pointer_constraints::zv1::client::zwp_confined_pointer_v1::ZwpConfinedPointerV1;
+use wayland_protocols::wp::pointer_constraints::zv1::client::zwp_pointer_constraints_v1::{
+    Lifetime, ZwpPointerConstraintsV1,
+};
+
+/// The compositor's `zwp_pointer_constraints_v1`, bound on GDK's own Wayland connection so
+/// it can confine the pointer to halo's window.
+pub struct PointerConstraints {
+    connection: Connection,
+    queue: EventQueue<Events>,
+    constraints: ZwpPointerConstraintsV1,
+    confined: Option<ZwpConfinedPointerV1>,
+}
+
+/// Nothing here reacts to the protocol's events, they're only drained.
+struct Events;
+
+impl PointerConstraints {
+    /// `None` off Wayland, or when the compositor doesn't offer pointer constraints.
+    pub fn bind() -> Option<Self> {
+        let display = gdk::Display::default()?.downcast::<WaylandDisplay>().ok()?;
+        let backend = display.wl_display()?.backend().upgrade()?;
+        let connection = Connection::from_backend(backend);
+        let (globals, queue) = registry_queue_init::<Events>(&connection).ok()?;
+        let constraints = globals.bind(&queue.handle(), 1..=1, ()).ok()?;
+        Some(Self {
+            connection,
+            queue,
+            constraints,
+            confined: None,
+        })
+    }
+
+    /// Confines the pointer to `window`'s surface, whenever the pointer is over it, until
+    /// [`release`](Self::release). The compositor also keeps it within the input region.
+    /// Returns `false` if the window has no Wayland surface or the seat no pointer yet.
+    pub fn confine(&mut self, window: &gtk::ApplicationWindow) -> bool {
+        self.release();
+        let surface = window
+            .surface()
+            .and_then(|s| s.downcast::<WaylandSurface>().ok())
+            .and_then(|s| s.wl_surface());
+        let pointer = gdk::Display::default()
+            .and_then(|d| d.default_seat())
+            .and_then(|s| s.pointer())
+            .and_then(|p| p.downcast::<WaylandDevice>().ok())
+            .and_then(|p| p.wl_pointer());
+        let (Some(surface), Some(pointer)) = (surface, pointer) else {
+            return false;
+        };
+        self.confined = Some(self.constraints.confine_pointer(
+            &surface,
+            &pointer,
+            None,
+            Lifetime::Persistent,
+            &self.queue.handle(),
+            (),
+        ));
+        let _ = self.connection.flush();
+        true
+    }
+
+    /// Lets the pointer go again. Confining a surface that's already confined is a protocol
+    /// error, which would take GDK's connection down with it, so this must come first.
+    pub fn release(&mut self) {
+        if let Some(confined) = self.confined.take() {
+            confined.destroy();
+            let _ = self.connection.flush();
+        }
+        let _ = self.queue.dispatch_pending(&mut Events);
+    }
+}
+
+impl Dispatch<WlRegistry, GlobalListContents> for Events {
+    fn event(
+        _: &mut Self,
+        _: &WlRegistry,
+        _: <WlRegistry as Proxy>::Event,
+        _: &GlobalListContents,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+delegate_noop!(Events: ZwpPointerConstraintsV1);
+delegate_noop!(Events: ignore ZwpConfinedPointerV1);
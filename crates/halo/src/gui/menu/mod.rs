@@ -3,23 +3,42 @@ use std::f64::consts::PI;
 pub mod model;
 pub mod view;
 
-pub use model::{CursorAction, Slot, SlotGeometry, State};
+pub use model::{Band, CursorAction, IconData, Slot, SlotGeometry, SlotScaling, State};
 pub use view::draw;
 
 pub const SLOT_COUNT: usize = 8;
 pub const REFERENCE_HEIGHT: f64 = 1440.0;
+pub const MIN_MONITOR_HEIGHT: f64 = 480.0; // smaller reported heights are clamped up to this
 pub const ICON_SIZE: i32 = 256;
 pub const INNER_RADIUS: f64 = 48.0; // hover distance (close)
 pub const OUTER_RADIUS: f64 = 128.0; // activation distance (run-or-raise)
 pub const MENU_RADIUS: f64 = 120.0; // slot orbital radius
 pub const SLOT_RADIUS: f64 = 52.0; // slot bg circle size
 pub const CENTER_CIRCLE_RADIUS: f64 = 32.0;
+pub const PIE_OUTER_RADIUS: f64 = 172.0; // outer edge of the sectors in the pie style
+pub const PIE_GAP: f64 = 2.0; // space between neighbouring sectors
 pub const ANGLE_STEP: f64 = 2.0 * PI / SLOT_COUNT as f64;
 pub const START_OFFSET: f64 = -PI / 2.0;
+pub const INPUT_REGION_PADDING: f64 = 24.0; // extra input margin beyond the ring
 pub const ICON_INACTIVE_ALPHA: f64 = 0.6;
+pub const PARENT_RING_ALPHA: f64 = 0.25; // rings above an open submenu
+pub const ICON_SHADOW_ALPHA: f64 = 0.45;
+pub const ICON_SHADOW_OFFSET: f64 = 0.04; // relative to the slot radius
+pub const ICON_SHADOW_SOFTNESS: f64 = 0.03; // spread of the passes that fake a blur
+pub const SLOT_SCALE_MAX: f64 = 2.5; // how large a slot with free space around it may grow
+pub const SLOT_SCALE_CURVE: f64 = 0.5; // exponent applied to the free space (sqrt)
+// Usage weighting: the most launched slot grows by this fraction, the rest by less
+pub const USAGE_MAX_GROWTH: f64 = 0.2;
+pub const FLASH_DURATION_MS: u64 = 180; // activation pulse before the menu hides
+pub const FLASH_GROWTH: f64 = 0.15; // how much a pulsing slot swells at its peak
+pub const CLOSE_DURATION_MS: u64 = 120; // fade out when the menu hides
+pub const CLOSE_SHRINK: f64 = 0.1; // how much smaller the ring is once it has faded out
+
 pub const SUB_KEYS: &[char] = &['a', 's', 'd', 'f', 'q', 'w', 'e', 'r', 'z', 'x', 'c', 'v'];
 
 // Subslot configuration
 pub const SUBSLOT_RING_RADIUS_FACTOR: f64 = 1.6; // How far out the ring is (relative to OUTER_RADIUS)
 pub const SUBSLOT_SIZE_FACTOR: f64 = 0.4; // Size of subslot circle relative to SLOT_RADIUS
 pub const SUBSLOT_SCALE_FACTOR: f64 = 0.6; // Internal scale for text/icons
+pub const WORKSPACE_LABEL_SIZE: f64 = 13.0; // font size of the labels on grouped subslots
+pub const WORKSPACE_LABEL_GAP: f64 = 6.0; // between a workspace's subslots and its label
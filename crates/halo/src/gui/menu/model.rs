@@ -1,15 +1,29 @@
-use crate::config::{Config, SlotConfig};
+use crate::config::{
+    ActivationConfig, CenterContent, Config, DimMode, Direction, IconTint, InputMode, MenuColors,
+    MenuStyle, SlotColors, SlotConfig,
+};
 use crate::gui::menu::{
-    ANGLE_STEP, ICON_SIZE, INNER_RADIUS, MENU_RADIUS, OUTER_RADIUS, REFERENCE_HEIGHT, SLOT_COUNT,
-    SLOT_RADIUS, START_OFFSET, SUB_KEYS, SUBSLOT_RING_RADIUS_FACTOR, SUBSLOT_SCALE_FACTOR,
-    SUBSLOT_SIZE_FACTOR,
+    ANGLE_STEP, CLOSE_DURATION_MS, FLASH_DURATION_MS, ICON_SIZE, INNER_RADIUS, MENU_RADIUS,
+    MIN_MONITOR_HEIGHT, OUTER_RADIUS, PARENT_RING_ALPHA, PIE_OUTER_RADIUS, REFERENCE_HEIGHT,
+    SLOT_COUNT, SLOT_RADIUS, SLOT_SCALE_CURVE, SLOT_SCALE_MAX, START_OFFSET, SUB_KEYS,
+    SUBSLOT_RING_RADIUS_FACTOR, SUBSLOT_SCALE_FACTOR, SUBSLOT_SIZE_FACTOR, USAGE_MAX_GROWTH,
+    WORKSPACE_LABEL_GAP, WORKSPACE_LABEL_SIZE,
 };
 use derive_more::{From, Into};
-use gdk_pixbuf::Pixbuf;
-use hypraise::desktop::{self, AppInfo, AppQuery};
-use hypraise::wm::{ActiveClient, Point, WindowClass, get_active_clients};
+use gdk_pixbuf::{Colorspace, Pixbuf, PixbufLoader};
+use hypraise::desktop::{self, AppInfo, AppName, AppQuery, Exec, ExecCommand, SETUP_EXEC};
+use hypraise::icon::{self, IconName};
+use hypraise::wm::{
+    ActiveClient, Address, KeyBind, MonitorId, Point, WindowClass, get_active_clients,
+    get_active_monitor_id,
+};
+use palette::Srgba;
+use serde::Serialize;
+use std::collections::HashMap;
 use std::f64::consts::PI;
 use std::ops::{Add, Div, Mul, Sub};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, From, Into)]
 pub struct Radians(pub f64);
@@ -84,6 +98,23 @@ impl AngularSegment {
     }
 }
 
+/// How much a slot grows into the space left by empty neighbours:
+/// `(space / ANGLE_STEP).powf(curve).min(max)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SlotScaling {
+    pub max: f64,
+    pub curve: f64,
+}
+
+impl Default for SlotScaling {
+    fn default() -> Self {
+        Self {
+            max: SLOT_SCALE_MAX,
+            curve: SLOT_SCALE_CURVE,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SlotGeometry {
     pub center: Point,
@@ -108,6 +139,7 @@ impl SlotGeometry {
         filled_indices: &[usize],
         center: Point,
         scale_factor: f64,
+        scaling: SlotScaling,
     ) -> Self {
         let current_pos = filled_indices.iter().position(|&x| x == index).unwrap();
         let prev_idx =
@@ -131,7 +163,7 @@ impl SlotGeometry {
         // average available space to scale the icon size
         // basically, room to breathe relative to slot density
         let width = (d_l + d_r) / 2.0;
-        let scale = (width / ANGLE_STEP).sqrt().min(2.5);
+        let scale = (width / ANGLE_STEP).powf(scaling.curve).min(scaling.max);
         let current_slot_radius = SLOT_RADIUS * scale * scale_factor;
 
         let angle = Self::angle(index);
@@ -147,6 +179,30 @@ impl SlotGeometry {
         }
     }
 
+    /// Where slot `index` draws its content in the pie style: the centroid of its sector, with
+    /// the largest circle that fits inside the sector there.
+    pub fn sector(index: usize, center: Point, scale_factor: f64) -> Self {
+        let inner = INNER_RADIUS * scale_factor;
+        let outer = PIE_OUTER_RADIUS * scale_factor;
+        let half = ANGLE_STEP / 2.0;
+        // centroid of an annular sector
+        let distance = 2.0 / 3.0 * (outer.powi(3) - inner.powi(3))
+            / (outer.powi(2) - inner.powi(2))
+            * half.sin()
+            / half;
+        let radius = (distance * half.sin()).min((outer - inner) / 2.0);
+
+        let angle = Self::angle(index);
+        Self {
+            center: Point::new(
+                center.x + distance * angle.cos(),
+                center.y + distance * angle.sin(),
+            ),
+            radius,
+            scale: radius / (SLOT_RADIUS * scale_factor),
+        }
+    }
+
     pub fn calculate_ring(index: usize, total: usize, center: Point, scale_factor: f64) -> Self {
         let angle = Radians(-PI / 2.0 + (index as f64 / total as f64) * 2.0 * PI);
         Self::from_angle(angle, center, scale_factor)
@@ -172,48 +228,307 @@ impl SlotGeometry {
 pub struct Slot {
     pub app: Option<AppInfo>,
     pub pixbuf: Option<Pixbuf>,
+    /// The icon looks like a single-color symbol, so tinting it won't lose detail
+    pub monochrome: bool,
+    /// The icon is still being loaded in the background; drawn as a spinner until supplied
+    pub loading: bool,
+    /// Hide the app when it's activated while already focused
+    pub toggle: bool,
+    /// Raise the running instance rather than ever launching another
+    pub single_instance: bool,
+    pub colors: SlotColors,
+    /// Slots of the nested ring this slot opens instead of launching
+    pub children: Vec<Slot>,
+    /// A URL or path opened with `xdg-open` instead of running the app
+    pub open: Option<String>,
+    /// Launched in place of the slot's action from the left and right halves of the slot
+    pub left_exec: Option<Exec>,
+    pub right_exec: Option<Exec>,
+    /// The app's open windows when the menu last opened, in Hyprland's order
+    pub windows: Vec<Address>,
+    /// The config this slot was built from, so a reload can tell whether it changed
+    pub config: Option<SlotConfig>,
+}
+
+/// An icon decoded off the GTK thread. A `Pixbuf` can't cross threads, so its pixels do.
+#[derive(Debug)]
+pub struct IconData {
+    pixels: glib::Bytes,
+    has_alpha: bool,
+    bits_per_sample: i32,
+    width: i32,
+    height: i32,
+    rowstride: i32,
+}
+
+impl IconData {
+    /// Decodes the icon file at `path` at the size slots are drawn from.
+    pub fn load(path: &Path) -> Option<Self> {
+        Pixbuf::from_file_at_scale(path, ICON_SIZE, ICON_SIZE, true)
+            .ok()
+            .map(|pixbuf| Self::from_pixbuf(&pixbuf))
+    }
+
+    fn from_pixbuf(pixbuf: &Pixbuf) -> Self {
+        Self {
+            pixels: pixbuf.read_pixel_bytes(),
+            has_alpha: pixbuf.has_alpha(),
+            bits_per_sample: pixbuf.bits_per_sample(),
+            width: pixbuf.width(),
+            height: pixbuf.height(),
+            rowstride: pixbuf.rowstride(),
+        }
+    }
+
+    fn into_pixbuf(self) -> Pixbuf {
+        Pixbuf::from_bytes(
+            &self.pixels,
+            Colorspace::Rgb,
+            self.has_alpha,
+            self.bits_per_sample,
+            self.width,
+            self.height,
+            self.rowstride,
+        )
+    }
 }
 
 impl Slot {
+    /// A slot for `app`. Its icon file is left to the background preload, see
+    /// [`State::pending_icons`]; only the bundled setup icon is loaded right away.
     pub fn new(app: Option<AppInfo>) -> Self {
-        let pixbuf = app.as_ref().and_then(Self::load_icon);
-        Self { app, pixbuf }
+        let pixbuf = app
+            .as_ref()
+            .filter(|a| a.exec.is_setup())
+            .and_then(Self::load_icon);
+        let mut slot = Self {
+            app,
+            monochrome: pixbuf.as_ref().is_some_and(looks_monochrome),
+            pixbuf,
+            loading: false,
+            toggle: false,
+            single_instance: false,
+            colors: SlotColors::default(),
+            children: Vec::new(),
+            open: None,
+            left_exec: None,
+            right_exec: None,
+            windows: Vec::new(),
+            config: None,
+        };
+        slot.loading = slot.icon_path().is_some();
+        slot
+    }
+
+    /// The icon file to decode for the slot, if it has one.
+    fn icon_path(&self) -> Option<&Path> {
+        self.app
+            .as_ref()
+            .filter(|app| !app.exec.is_setup() && !app.icon.as_os_str().is_empty())
+            .map(|app| app.icon.as_path())
     }
 
     fn load_icon(app: &AppInfo) -> Option<Pixbuf> {
+        if app.exec.is_setup() {
+            return load_setup_icon();
+        }
         (!app.icon.as_os_str().is_empty())
             .then(|| Pixbuf::from_file_at_scale(&app.icon, ICON_SIZE, ICON_SIZE, true).ok())?
     }
 
+    /// Supplies a background-loaded icon, ending the loading state.
+    pub fn set_pixbuf(&mut self, pixbuf: Option<Pixbuf>) {
+        self.monochrome = pixbuf.as_ref().is_some_and(looks_monochrome);
+        self.pixbuf = pixbuf;
+        self.loading = false;
+    }
+
+    /// Re-resolves the icon path, e.g. after an icon theme change, and has the preload decode
+    /// it again. The old icon stays up until the new one is in.
+    pub fn reload_icon(&mut self) {
+        if let Some(app) = &mut self.app {
+            app.reload_icon();
+        }
+        if self.app.as_ref().is_some_and(|app| app.exec.is_setup()) {
+            return;
+        }
+        self.loading = self.icon_path().is_some();
+        if !self.loading {
+            self.pixbuf = None;
+            self.monochrome = false;
+        }
+    }
+
+    /// The color to paint the icon in, if any: the slot's own tint over `default`. Full-color
+    /// icons are only tinted when the tint is forced.
+    pub fn icon_tint(&self, default: Option<IconTint>) -> Option<Srgba<f64>> {
+        self.config
+            .as_ref()
+            .and_then(|c| c.icon_tint)
+            .or(default)
+            .filter(|tint| tint.force() || self.monochrome)
+            .map(|tint| tint.color())
+    }
+
     pub fn empty() -> Self {
         Self {
             app: None,
             pixbuf: None,
+            monochrome: false,
+            loading: false,
+            toggle: false,
+            single_instance: false,
+            colors: SlotColors::default(),
+            children: Vec::new(),
+            open: None,
+            left_exec: None,
+            right_exec: None,
+            windows: Vec::new(),
+            config: None,
         }
     }
 
     pub fn from_config(cfg: &SlotConfig) -> Self {
+        Self {
+            config: Some(cfg.clone()),
+            ..Self::build(cfg)
+        }
+    }
+
+    fn build(cfg: &SlotConfig) -> Self {
+        // the setup slot is synthetic, so there's nothing to look up
+        if cfg.exec.as_ref().is_some_and(Exec::is_setup) {
+            return Self::new(Some(AppInfo::from_parts(
+                AppName::new("Setup"),
+                PathBuf::new(),
+                cfg.class
+                    .clone()
+                    .unwrap_or_else(|| WindowClass::new("halo-setup")),
+                ExecCommand::new(SETUP_EXEC),
+            )));
+        }
+
+        if let Some(target) = &cfg.open {
+            // there's no window to match, so the class stays empty
+            let name = cfg
+                .name
+                .as_deref()
+                .or(cfg.app.as_ref().map(|q| q.as_str()))
+                .unwrap_or(target);
+            let app = AppInfo::from_parts(
+                AppName::new(name),
+                icon::find_icon_path(&IconName::new(open_icon_name(target))).unwrap_or_default(),
+                WindowClass::new(""),
+                ExecCommand::new(""),
+            );
+            return Self {
+                open: Some(target.clone()),
+                colors: cfg.colors,
+                ..Self::new(Some(app))
+            };
+        }
+
+        if !cfg.children.is_empty() {
+            // groups launch nothing, so `app` is just a label and an icon name
+            let icon_name = cfg.app.as_ref().map_or("Group", |q| q.as_str());
+            let app = AppInfo::from_parts(
+                AppName::new(cfg.name.as_deref().unwrap_or(icon_name)),
+                icon::find_icon_path(&IconName::new(icon_name)).unwrap_or_default(),
+                cfg.class.clone().unwrap_or_else(|| WindowClass::new("")),
+                ExecCommand::new(""),
+            );
+            return Self {
+                colors: cfg.colors,
+                children: build_ring(&cfg.children),
+                ..Self::new(Some(app))
+            };
+        }
+
         let app = cfg
             .app
             .as_ref()
             .map(|query| AppInfo::new(query, cfg.class.clone(), cfg.exec.clone()));
-        Self::new(app)
+        Self::for_app(app, cfg)
+    }
+
+    /// A slot for `app`, the one `cfg`'s query found, with the rest of `cfg` applied over it.
+    fn for_app(mut app: Option<AppInfo>, cfg: &SlotConfig) -> Self {
+        let mut half_execs = (None, None);
+        if let (Some(app), Some(query)) = (&mut app, &cfg.app) {
+            let interpolate =
+                |exec: &Option<Exec>| exec.as_ref().map(|e| app.interpolate_exec(e, query));
+            half_execs = (interpolate(&cfg.left_exec), interpolate(&cfg.right_exec));
+            if let Some(name) = &cfg.name {
+                app.name = AppName::new(name.as_str());
+            }
+        }
+        let (left_exec, right_exec) = half_execs;
+        Self {
+            toggle: cfg.toggle,
+            single_instance: cfg.single_instance,
+            colors: cfg.colors,
+            left_exec,
+            right_exec,
+            ..Self::new(app)
+        }
+    }
+
+    /// The exec launched from `half` of the slot, if that half has its own.
+    pub fn half_exec(&self, half: SlotHalf) -> Option<&Exec> {
+        match half {
+            SlotHalf::Left => self.left_exec.as_ref(),
+            SlotHalf::Right => self.right_exec.as_ref(),
+        }
     }
 
     pub fn is_running(&self, active_classes: &[WindowClass]) -> bool {
+        self.has_window_in(active_classes)
+    }
+
+    /// One of the slot's windows wants attention.
+    pub fn is_urgent(&self, urgent_classes: &[WindowClass]) -> bool {
+        self.has_window_in(urgent_classes)
+    }
+
+    fn has_window_in<'c>(&self, classes: impl IntoIterator<Item = &'c WindowClass>) -> bool {
+        // groups and open slots have no class, and no window of theirs can be running
         self.app.as_ref().is_some_and(|app| {
-            active_classes
-                .iter()
-                .any(|c| c.to_lowercase() == app.class.to_lowercase())
+            !app.class.is_empty()
+                && classes
+                    .into_iter()
+                    .any(|c| c.to_lowercase() == app.class.to_lowercase())
         })
     }
 
+    /// The window to cycle to after `current`: the next of [`Self::windows`], wrapping
+    /// around, or the first when `current` isn't one of them.
+    pub fn window_after(&self, current: Option<&Address>) -> Option<&Address> {
+        let next = current
+            .and_then(|address| self.windows.iter().position(|w| w == address))
+            .map_or(0, |i| (i + 1) % self.windows.len());
+        self.windows.get(next)
+    }
+
     pub fn is_broken(&self) -> bool {
-        self.app
-            .as_ref()
-            .map(|a| a.exec.as_str().is_empty())
-            .unwrap_or(false)
+        !self.is_group()
+            && self.open.is_none()
+            && self
+                .app
+                .as_ref()
+                .map(|a| a.exec.is_empty())
+                .unwrap_or(false)
     }
+
+    pub fn is_group(&self) -> bool {
+        !self.children.is_empty()
+    }
+}
+
+/// A side of a slot's circle, split by the vertical line through its center.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotHalf {
+    Left,
+    Right,
 }
 
 #[derive(Clone)]
@@ -224,15 +539,169 @@ pub struct SubSlot {
     pub pixbuf: Option<Pixbuf>,
 }
 
+impl SubSlot {
+    /// The text drawn when there's no icon: the class, or the title for windows without one.
+    pub fn label(&self) -> &str {
+        if self.client.class.is_empty() {
+            &self.client.title
+        } else {
+            &self.client.class
+        }
+    }
+
+    /// The icon of the desktop entry matching the client's class, if it has a class.
+    fn load_icon(client: &ActiveClient) -> Option<Pixbuf> {
+        if client.class.is_empty() {
+            return None;
+        }
+        let query = AppQuery::new(client.class.to_string());
+        desktop::find_desktop_entry(&query)
+            .as_ref()
+            .and_then(Slot::load_icon)
+    }
+}
+
 pub struct State {
     pub center: Point,
     pub slots: Vec<Slot>,
     pub subslots: Vec<SubSlot>,
     pub hover_index: Option<usize>,
+    /// The slot picked with the arrow keys, which Enter activates; drawn with an outline so it
+    /// stays apart from the pointer's hover
+    pub keyboard_index: Option<usize>,
     pub active_classes: Vec<WindowClass>,
+    /// Classes of the windows asking for attention
+    pub urgent_classes: Vec<WindowClass>,
     pub scale_factor: f64,
+    /// Bounds on the ring's width in pixels, which the scale from the monitor height is
+    /// clamped to
+    pub min_diameter: Option<f64>,
+    pub max_diameter: Option<f64>,
+    /// Overrides for the theme's colors
+    pub colors: MenuColors,
     pub slot_geometries: Vec<Option<SlotGeometry>>,
     pub show_subslots: bool,
+    pub skip_classless_windows: bool,
+    pub group_subslots_by_workspace: bool,
+    pub subslots_on_active_monitor: bool,
+    /// The monitor the menu opened on, looked up when subslots are limited to it
+    pub active_monitor: Option<MonitorId>,
+    pub limit_input_region: bool,
+    /// Quick-switch mode: only open windows are shown, spread over the whole ring
+    pub switch_mode: bool,
+    /// Running-only mode: slots whose app isn't running are left out of the ring as if empty
+    pub running_only: bool,
+    /// Whether a plain show starts in running-only mode
+    pub running_only_by_default: bool,
+    /// Category mode: the ring is the apps of this desktop entry category, spread like the
+    /// quick-switch windows
+    pub category: Option<String>,
+    /// The configured top-level slots, put aside while a category ring is shown
+    configured_slots: Option<Vec<Slot>>,
+    pub subslot_hover: Option<usize>,
+    /// Draw app names alongside icons, toggled while the menu is open
+    pub labels_visible: bool,
+    pub dim_mode: DimMode,
+    pub style: MenuStyle,
+    pub ignore_special_workspaces: bool,
+    pub input_mode: InputMode,
+    pub show_keybind_hints: bool,
+    /// Keybind labels per slot, see [`State::set_keybind_hints`]
+    pub keybind_hints: Vec<Option<String>>,
+    pub activation_cooldown: Duration,
+    /// The rings above the current one while a group is open, innermost last
+    parent_menus: Vec<ParentMenu>,
+    pub weight_by_usage: bool,
+    /// Launch counts by lowercased class, see [`crate::sys::usage::UsageStats`]
+    pub usage_counts: HashMap<String, u64>,
+    pub activation: ActivationConfig,
+    pub slot_scaling: SlotScaling,
+    pub return_focus_after_close: bool,
+    pub hide_on_focus_loss: bool,
+    pub icon_shadow: bool,
+    pub icon_tint: Option<IconTint>,
+    pub live_client_updates: bool,
+    /// Keep the pointer on the menu's monitor while it's open
+    pub confine_pointer: bool,
+    pub center_content: Option<CenterContent>,
+    /// The image for [`CenterContent::Icon`], loaded when the config is applied
+    pub center_icon: Option<Pixbuf>,
+    /// For [`CenterContent::Workspace`], read on every show
+    pub workspace_name: Option<String>,
+    pub parent_ring_alpha: f64,
+    /// The band the cursor was last seen in, so activations fire once on entering a band
+    cursor_band: Band,
+    /// Where and when the cursor was last seen, to tell its speed
+    last_cursor: Option<(Point, Instant)>,
+    /// A band was entered too fast to activate, so it fires once the pointer slows down
+    activation_pending: bool,
+    /// The slot index that just launched something and when, pulsing before the menu hides
+    pub flash: Option<(usize, Instant)>,
+    pub subslot_fan_out: Duration,
+    /// GTK's `gtk-enable-animations`; when off, every transition is instant
+    pub animations_enabled: bool,
+    /// The keys handed out to subslots, in order
+    pub sub_keys: Vec<char>,
+    /// When the menu last showed, which the subslots fan out from
+    pub shown_at: Option<Instant>,
+    /// When the menu started closing, if it's fading out right now
+    pub closing_at: Option<Instant>,
+}
+
+/// Output of the `subslots` debug command, see [`State::subslot_report`].
+#[derive(Debug, Serialize)]
+pub struct SubslotReport {
+    /// `show_active_clients` is on, or the menu was opened in quick-switch mode
+    pub enabled: bool,
+    pub clients: Vec<ClientReport>,
+    pub excluded: Vec<WindowClass>,
+    pub subslots: Vec<SubslotAssignment>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ClientReport {
+    pub class: WindowClass,
+    pub title: String,
+    pub workspace: String,
+    pub address: String,
+}
+
+impl From<&ActiveClient> for ClientReport {
+    fn from(client: &ActiveClient) -> Self {
+        Self {
+            class: client.class.clone(),
+            title: client.title.to_string(),
+            workspace: client.workspace_name.clone(),
+            address: client.address.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct SubslotAssignment {
+    pub key: char,
+    #[serde(flatten)]
+    pub client: ClientReport,
+}
+
+/// What a submenu replaced, restored when leaving it.
+struct ParentMenu {
+    slots: Vec<Slot>,
+    keybind_hints: Vec<Option<String>>,
+    /// Where the slots were drawn, so the ring can stay visible behind its submenu
+    geometries: Vec<Option<SlotGeometry>>,
+    /// The group that was opened
+    opened: usize,
+    /// The ring's center, as submenus are centered on the cursor instead
+    center: Point,
+}
+
+/// A ring above the open submenu, as drawn behind it.
+pub struct ParentRing<'a> {
+    pub slots: &'a [Slot],
+    pub geometries: &'a [Option<SlotGeometry>],
+    pub opened: usize,
+    pub center: Point,
 }
 
 impl State {
@@ -248,169 +717,1098 @@ impl State {
             slots,
             subslots: Vec::new(),
             hover_index: None,
+            keyboard_index: None,
             active_classes,
+            urgent_classes: Vec::new(),
             scale_factor,
+            min_diameter: None,
+            max_diameter: None,
+            colors: MenuColors::default(),
             slot_geometries: Vec::new(),
             show_subslots,
+            skip_classless_windows: false,
+            group_subslots_by_workspace: false,
+            subslots_on_active_monitor: false,
+            active_monitor: None,
+            limit_input_region: false,
+            switch_mode: false,
+            running_only: false,
+            running_only_by_default: false,
+            category: None,
+            configured_slots: None,
+            subslot_hover: None,
+            labels_visible: false,
+            dim_mode: DimMode::default(),
+            style: MenuStyle::default(),
+            ignore_special_workspaces: false,
+            input_mode: InputMode::default(),
+            show_keybind_hints: false,
+            keybind_hints: Vec::new(),
+            activation_cooldown: Duration::ZERO,
+            parent_menus: Vec::new(),
+            weight_by_usage: false,
+            usage_counts: HashMap::new(),
+            activation: ActivationConfig::default(),
+            slot_scaling: SlotScaling::default(),
+            return_focus_after_close: false,
+            hide_on_focus_loss: false,
+            icon_shadow: false,
+            icon_tint: None,
+            live_client_updates: false,
+            confine_pointer: false,
+            center_content: None,
+            center_icon: None,
+            workspace_name: None,
+            parent_ring_alpha: PARENT_RING_ALPHA,
+            subslot_fan_out: Duration::ZERO,
+            animations_enabled: true,
+            sub_keys: SUB_KEYS.to_vec(),
+            shown_at: None,
+            closing_at: None,
+            cursor_band: Band::Dead,
+            last_cursor: None,
+            activation_pending: false,
+            flash: None,
         };
         state.recalculate_geometries();
         state
     }
 
     pub fn init_slots(config: &Config) -> Vec<Slot> {
-        let mut slots = vec![Slot::empty(); SLOT_COUNT];
+        build_ring(&config.slots)
+    }
 
-        config
-            .slots
+    /// Rebuilds only the top-level slots whose config differs from `configs`, so unchanged
+    /// slots keep their loaded icons. Returns how many were rebuilt.
+    pub fn update_slots(&mut self, configs: &[SlotConfig]) -> usize {
+        self.leave_all_submenus();
+        self.restore_configured_slots();
+        // later entries win, as in `build_ring`
+        let mut wanted: Vec<Option<&SlotConfig>> = vec![None; SLOT_COUNT];
+        for cfg in configs.iter().filter(|cfg| cfg.enabled) {
+            if let Some(dir) = cfg.direction {
+                wanted[dir.as_index()] = Some(cfg);
+            }
+        }
+
+        let mut rebuilt = 0;
+        for (slot, cfg) in self.slots.iter_mut().zip(wanted) {
+            if slot.config.as_ref() != cfg {
+                *slot = cfg.map_or_else(Slot::empty, Slot::from_config);
+                rebuilt += 1;
+            }
+        }
+        self.hover_index = None;
+        self.keyboard_index = None;
+        self.recalculate_geometries();
+        rebuilt
+    }
+
+    /// Resolves the slots that found no app again, nested ones included, after the desktop
+    /// entries were rescanned. Returns how many were rebuilt.
+    pub fn rebuild_broken_slots(&mut self) -> usize {
+        fn rebuild(slots: &mut [Slot]) -> usize {
+            slots
+                .iter_mut()
+                .map(|slot| match &slot.config {
+                    Some(cfg) if slot.is_broken() => {
+                        *slot = Slot::from_config(&cfg.clone());
+                        1
+                    }
+                    _ => rebuild(&mut slot.children),
+                })
+                .sum()
+        }
+        self.leave_all_submenus();
+        let rebuilt = rebuild(&mut self.slots)
+            + self
+                .configured_slots
+                .as_mut()
+                .map_or(0, |slots| rebuild(slots));
+        self.recalculate_geometries();
+        rebuilt
+    }
+
+    /// Replaces the top-level slots, closing any open submenu.
+    pub fn set_slots(&mut self, slots: Vec<Slot>) {
+        self.parent_menus.clear();
+        self.configured_slots = None;
+        self.slots = slots;
+        self.hover_index = None;
+        self.keyboard_index = None;
+        self.recalculate_geometries();
+    }
+
+    pub fn apply_config(&mut self, config: &Config) {
+        self.show_subslots = config.show_active_clients;
+        self.skip_classless_windows = config.skip_classless_windows;
+        self.group_subslots_by_workspace = config.group_subslots_by_workspace;
+        self.subslots_on_active_monitor = config.subslots_on_active_monitor;
+        self.limit_input_region = config.limit_input_region;
+        self.dim_mode = config.dim_mode;
+        self.style = config.style;
+        self.ignore_special_workspaces = config.ignore_special_workspaces;
+        self.input_mode = config.input_mode;
+        self.show_keybind_hints = config.show_keybind_hints;
+        self.activation_cooldown = Duration::from_millis(config.activation_cooldown_ms);
+        self.weight_by_usage = config.weight_by_usage;
+        self.activation = config.activation;
+        self.slot_scaling = SlotScaling {
+            max: config.slot_scale_max,
+            curve: config.slot_scale_curve,
+        };
+        self.return_focus_after_close = config.return_focus_after_close;
+        self.hide_on_focus_loss = config.hide_on_focus_loss;
+        self.icon_shadow = config.icon_shadow;
+        self.running_only_by_default = config.running_only;
+        self.icon_tint = config.icon_tint;
+        self.live_client_updates = config.live_client_updates;
+        self.confine_pointer = config.confine_pointer;
+        // launching lives in hypraise, which keeps the setting itself
+        hypraise::wm::set_systemd_scope(config.use_systemd_scope);
+        hypraise::wm::set_match_initial_class(config.match_initial_class);
+        self.center_content = config.center_content.clone();
+        self.parent_ring_alpha = config.parent_ring_alpha;
+        self.min_diameter = config.min_diameter_px;
+        self.max_diameter = config.max_diameter_px;
+        self.colors = config.colors;
+        self.subslot_fan_out = Duration::from_millis(config.subslot_fan_out_ms);
+        self.sub_keys = config
+            .subslot_keys
+            .as_ref()
+            .map_or_else(|| SUB_KEYS.to_vec(), |keys| keys.0.clone());
+        self.center_icon = match &self.center_content {
+            Some(CenterContent::Icon(icon)) => load_center_icon(icon),
+            _ => None,
+        };
+        if !self.show_keybind_hints {
+            self.keybind_hints.clear();
+        }
+    }
+
+    /// Matches `exec` binds that run `hypraise <query>` to the slot whose app has that name
+    /// or class. Nothing checks that hypraise would resolve the query the same way, so this
+    /// is only a hint.
+    pub fn set_keybind_hints(&mut self, binds: &[KeyBind]) {
+        let queries: Vec<(&str, &KeyBind)> = binds
             .iter()
-            .filter_map(|cfg| cfg.direction.map(|dir| (dir, cfg)))
-            .for_each(|(dir, cfg)| {
-                slots[dir.as_index()] = Slot::from_config(cfg);
-            });
+            .filter(|b| b.dispatcher == "exec")
+            .filter_map(|b| hypraise_query(&b.arg).map(|q| (q, b)))
+            .collect();
 
-        slots
+        self.keybind_hints = self
+            .slots
+            .iter()
+            .map(|slot| {
+                let app = slot.app.as_ref()?;
+                queries
+                    .iter()
+                    .find(|(q, _)| {
+                        app.name.eq_ignore_ascii_case(q)
+                            || app.class.eq_ignore_ascii_case(q)
+                            || app
+                                .class
+                                .split('.')
+                                .any(|part| part.eq_ignore_ascii_case(q))
+                    })
+                    .map(|(_, b)| b.label())
+            })
+            .collect();
     }
 
-    pub fn update_cursor(&mut self, cursor: Point) -> CursorAction {
-        let dist = self.distance_from_center(cursor);
+    pub fn keybind_hint(&self, index: usize) -> Option<&str> {
+        self.keybind_hints.get(index)?.as_deref()
+    }
 
-        // dead zone
-        if dist <= INNER_RADIUS * self.scale_factor {
-            return self.clear_hover();
+    /// How far into its activation pulse the slot at `index` is, from 0 to 1 and back.
+    pub fn flash_amount(&self, index: usize) -> f64 {
+        match self.flash {
+            Some((i, started)) if i == index => flash_pulse(started.elapsed()),
+            _ => 0.0,
         }
+    }
 
-        let new_idx = self.find_nearest_slot(cursor);
-        let changed = self.hover_index != new_idx;
-        let activate = dist > OUTER_RADIUS * self.scale_factor && new_idx.is_some();
+    /// How far the subslots have fanned out from the center since the menu showed, from 0 to 1.
+    pub fn fan_out_progress(&self) -> f64 {
+        if !self.animations_enabled {
+            return 1.0;
+        }
+        self.shown_at.map_or(1.0, |shown| {
+            fan_out_ease(shown.elapsed(), self.subslot_fan_out)
+        })
+    }
 
-        self.hover_index = new_idx;
+    /// How far the menu has faded out since it started closing, from 0 to 1.
+    pub fn close_progress(&self) -> f64 {
+        self.closing_at.map_or(0.0, |closing| {
+            close_ease(closing.elapsed(), Duration::from_millis(CLOSE_DURATION_MS))
+        })
+    }
 
-        CursorAction::new(changed || activate, activate)
+    /// Whether there are subslots still on their way out.
+    pub fn fanning_out(&self) -> bool {
+        !self.subslots.is_empty() && self.fan_out_progress() < 1.0
     }
 
-    fn distance_from_center(&self, cursor: Point) -> f64 {
-        let (dx, dy) = (cursor.x - self.center.x, cursor.y - self.center.y);
-        dx.hypot(dy)
+    /// Replaces the slot in `direction` at runtime, e.g. from the `set-slot` command.
+    pub fn set_slot(&mut self, direction: Direction, slot: Slot) {
+        self.leave_all_submenus();
+        self.restore_configured_slots();
+        self.slots[direction.as_index()] = slot;
+        self.recalculate_geometries();
     }
 
-    fn clear_hover(&mut self) -> CursorAction {
-        let changed = self.hover_index.is_some();
+    /// Replaces the top level with a slot for each app, spread like the quick-switch ring and
+    /// capped the way subslots are. The configured slots come back on the next show.
+    pub fn show_category_ring(&mut self, mut apps: Vec<AppInfo>) {
+        self.leave_all_submenus();
+        let cap = self.sub_keys.len();
+        if apps.len() > cap {
+            let left_out: Vec<String> = apps.drain(cap..).map(|app| app.name.into()).collect();
+            log::info!(
+                "Category ring only fits {} apps, leaving out {}",
+                cap,
+                left_out.join(", ")
+            );
+        }
+        let ring = apps.into_iter().map(|app| Slot::new(Some(app))).collect();
+        let configured = std::mem::replace(&mut self.slots, ring);
+        self.configured_slots.get_or_insert(configured);
+        self.keybind_hints.clear();
         self.hover_index = None;
-        CursorAction::new(changed, false)
+        self.keyboard_index = None;
+        self.recalculate_geometries();
     }
 
-    fn cursor_angle(&self, cursor: Point) -> f64 {
-        let (dx, dy) = (cursor.x - self.center.x, cursor.y - self.center.y);
-        dy.atan2(dx)
+    /// The direction of the slot at `index`; category rings aren't laid out by direction.
+    pub fn slot_direction(&self, index: usize) -> Option<Direction> {
+        (!self.in_category_ring())
+            .then(|| Direction::from_index(index))
+            .flatten()
     }
 
-    fn find_nearest_slot(&self, cursor: Point) -> Option<usize> {
-        let cursor_angle = self.cursor_angle(cursor);
+    /// What to say in place of a ring with nothing in it, or `None` when something is shown.
+    pub fn empty_hint(&self) -> Option<String> {
+        if self.slot_geometries.iter().any(Option::is_some) || !self.subslots.is_empty() {
+            return None;
+        }
+        Some(if self.switch_mode {
+            "There are no open windows to switch to".to_string()
+        } else if let Some(category) = &self.category {
+            format!("No installed apps are in the {category} category")
+        } else if self.running_only {
+            "None of the apps in your slots are running".to_string()
+        } else {
+            "There are no slots to show. Add some to your config.toml".to_string()
+        })
+    }
 
-        (0..SLOT_COUNT)
-            .filter(|&i| self.slots[i].app.is_some())
-            .min_by(|&a, &b| {
-                SlotGeometry::angle_difference(cursor_angle, SlotGeometry::angle(a).0).total_cmp(
-                    &SlotGeometry::angle_difference(cursor_angle, SlotGeometry::angle(b).0),
-                )
-            })
+    pub fn in_category_ring(&self) -> bool {
+        self.configured_slots.is_some()
     }
 
-    pub fn get_hovered_app(&self) -> Option<&AppInfo> {
-        self.hover_index
-            .and_then(|idx| self.slots[idx].app.as_ref())
+    fn restore_configured_slots(&mut self) {
+        if let Some(slots) = self.configured_slots.take() {
+            self.slots = slots;
+        }
     }
 
-    pub fn refresh(
-        &mut self,
-        center: Point,
-        active_classes: Vec<WindowClass>,
-        monitor_height: f64,
-    ) {
-        self.active_classes = active_classes;
+    /// The style the ring is drawn in. A category ring has no fixed sectors, so it's always
+    /// circles.
+    pub fn drawn_style(&self) -> MenuStyle {
+        if self.in_category_ring() {
+            MenuStyle::Circles
+        } else {
+            self.style
+        }
+    }
+
+    pub fn in_submenu(&self) -> bool {
+        !self.parent_menus.is_empty()
+    }
+
+    /// Replaces the ring with the children of the group at `index`, centered on `center` so
+    /// a cursor that just flicked out to open it starts over in the dead zone.
+    /// Returns `false` if the slot isn't a group.
+    pub fn enter_submenu(&mut self, index: usize, center: Point) -> bool {
+        let Some(children) = self
+            .slots
+            .get(index)
+            .filter(|s| s.is_group())
+            .map(|s| s.children.clone())
+        else {
+            return false;
+        };
+
+        let slots = std::mem::replace(&mut self.slots, children);
+        let keybind_hints = std::mem::take(&mut self.keybind_hints);
+        let geometries = std::mem::take(&mut self.slot_geometries);
+        self.parent_menus.push(ParentMenu {
+            slots,
+            keybind_hints,
+            geometries,
+            opened: index,
+            center: self.center,
+        });
         self.center = center;
         self.hover_index = None;
-        self.scale_factor = monitor_height / REFERENCE_HEIGHT;
+        self.keyboard_index = None;
+        self.cursor_band = Band::Dead;
+        self.recalculate_geometries();
+        true
+    }
 
-        // subslots
-        self.subslots.clear();
-        if self.show_subslots {
-            let sub_clients = get_active_clients().into_iter().filter(|c| {
-                let slot_classes = self
-                    .slots
-                    .iter()
-                    .filter_map(|s| s.app.as_ref())
-                    .map(|app| app.class.to_lowercase())
-                    .collect::<Vec<_>>();
-                !slot_classes.contains(&c.class.to_lowercase())
-            });
+    /// Goes back up one level, keeping the current center. Returns `false` at the top level.
+    pub fn leave_submenu(&mut self) -> bool {
+        let Some(parent) = self.parent_menus.pop() else {
+            return false;
+        };
+        self.slots = parent.slots;
+        self.keybind_hints = parent.keybind_hints;
+        self.hover_index = None;
+        self.keyboard_index = None;
+        self.recalculate_geometries();
+        true
+    }
 
-            for (sc, shortcut) in sub_clients.zip(SUB_KEYS) {
-                let query = AppQuery::new(sc.class.to_string());
-                let app_info = desktop::find_desktop_entry(&query);
-                let pixbuf = app_info.as_ref().and_then(Slot::load_icon);
-
-                let geometry = SlotGeometry {
-                    center,
-                    radius: 0.0,
-                    scale: 0.0,
-                };
-
-                self.subslots.push(SubSlot {
-                    client: sc,
-                    key: *shortcut,
-                    geometry,
-                    pixbuf,
-                });
-            }
+    /// How many groups deep the open submenu is; 0 at the top level.
+    pub fn submenu_depth(&self) -> usize {
+        self.parent_menus.len()
+    }
+
+    /// The rings above the open submenu, outermost first.
+    pub fn parent_rings(&self) -> impl Iterator<Item = ParentRing<'_>> {
+        self.parent_menus.iter().map(|parent| ParentRing {
+            slots: &parent.slots,
+            geometries: &parent.geometries,
+            opened: parent.opened,
+            center: parent.center,
+        })
+    }
+
+    fn leave_all_submenus(&mut self) {
+        if !self.parent_menus.is_empty() {
+            let root = self.parent_menus.swap_remove(0);
+            self.parent_menus.clear();
+            self.slots = root.slots;
+            self.keybind_hints = root.keybind_hints;
         }
+    }
 
-        self.recalculate_geometries();
+    /// Whether `point` is in the center dead zone, where clicks go back out of a submenu.
+    pub fn in_dead_zone(&self, point: Point) -> bool {
+        self.band_at(self.distance_from_center(point)) == Band::Dead
     }
 
-    fn filled_slot_indices(&self) -> Vec<usize> {
-        self.slots
-            .iter()
-            .enumerate()
-            .filter_map(|(i, s)| s.app.as_ref().map(|_| i))
-            .collect()
+    fn band_at(&self, dist: f64) -> Band {
+        let scaled = |radius: f64| radius * self.scale_factor;
+        match self.activation.far_radius {
+            Some(far) if dist > scaled(far) => Band::Far,
+            _ if dist > scaled(self.activation.outer_radius) => Band::Near,
+            _ if dist > scaled(self.activation.inner_radius) => Band::Hover,
+            _ => Band::Dead,
+        }
     }
 
-    fn recalculate_geometries(&mut self) {
-        let filled_indices = self.filled_slot_indices();
-        self.slot_geometries = self.calculate_main_slots(&filled_indices);
+    pub fn update_cursor(&mut self, cursor: Point) -> CursorAction {
+        self.update_cursor_at(cursor, Instant::now())
+    }
 
-        let segments = self.find_free_segments();
-        self.distribute_subslots(&segments);
+    /// Cursor speed since the last update, in pixels per second at the reference height.
+    fn cursor_velocity(&self, cursor: Point, now: Instant) -> f64 {
+        let Some((last, at)) = self.last_cursor else {
+            return 0.0;
+        };
+        // events can share a timestamp, so don't divide by less than a millisecond
+        let elapsed = now.duration_since(at).as_secs_f64().max(0.001);
+        (cursor.x - last.x).hypot(cursor.y - last.y) / elapsed / self.scale_factor
     }
 
-    fn calculate_main_slots(&self, filled_indices: &[usize]) -> Vec<Option<SlotGeometry>> {
-        self.slots
+    fn update_cursor_at(&mut self, cursor: Point, now: Instant) -> CursorAction {
+        let band = self.band_at(self.distance_from_center(cursor));
+        let settled = self
+            .activation
+            .max_velocity
+            .is_none_or(|max| self.cursor_velocity(cursor, now) <= max);
+        self.last_cursor = Some((cursor, now));
+
+        if self.switch_mode {
+            // keep the current pick in the dead zone so keyboard cycling isn't undone
+            if band == Band::Dead {
+                return CursorAction::default();
+            }
+            let new_idx = self.find_nearest_subslot(cursor);
+            let changed = self.subslot_hover != new_idx;
+            self.subslot_hover = new_idx;
+            return CursorAction::new(changed, false);
+        }
+
+        if band == Band::Dead {
+            return CursorAction::new(self.reset_hover(), false);
+        }
+        // subslots can sit in a slot's direction, so the one under the cursor wins over it
+        if let Some(index) = self.subslot_at(cursor) {
+            let changed = self.subslot_hover != Some(index) || self.hover_index.is_some();
+            self.select_subslot(index);
+            self.cursor_band = band;
+            return CursorAction {
+                band,
+                ..CursorAction::new(changed, false)
+            };
+        }
+        let left_subslot = self.subslot_hover.take().is_some();
+
+        let new_idx = self.find_nearest_slot(cursor);
+        let changed = self.hover_index != new_idx;
+        let entered = changed || band != self.cursor_band || self.activation_pending;
+        // touch has no hover, so activation comes from taps instead (see `select_at`)
+        let wants_activation = self.input_mode == InputMode::Pointer
+            && matches!(band, Band::Near | Band::Far)
+            && entered
+            && new_idx.is_some();
+        let activate = wants_activation && settled;
+
+        self.hover_index = new_idx;
+        self.cursor_band = band;
+        self.activation_pending = wants_activation && !settled;
+
+        CursorAction {
+            band,
+            ..CursorAction::new(changed || activate || left_subslot, activate)
+        }
+    }
+
+    /// The subslot whose circle `point` is in, the one with the nearest center where they
+    /// overlap.
+    pub fn subslot_at(&self, point: Point) -> Option<usize> {
+        let distance = |g: &SlotGeometry| (point.x - g.center.x).hypot(point.y - g.center.y);
+        self.subslots
             .iter()
             .enumerate()
-            .map(|(i, slot)| {
-                slot.app.as_ref().map(|_| {
-                    SlotGeometry::calculate(i, filled_indices, self.center, self.scale_factor)
-                })
-            })
-            .collect()
+            .filter(|(_, s)| distance(&s.geometry) <= s.geometry.radius)
+            .min_by(|(_, a), (_, b)| distance(&a.geometry).total_cmp(&distance(&b.geometry)))
+            .map(|(i, _)| i)
     }
 
-    fn find_free_segments(&self) -> Vec<AngularSegment> {
-        let mut free_segments = vec![AngularSegment::new(-PI, PI)];
-
-        for (i, geom) in self.slot_geometries.iter().enumerate() {
-            if let Some(g) = geom {
-                let center_angle = SlotGeometry::angle(i).normalize();
+    /// The half of the hovered slot's circle that `point` is on, or `None` outside the circle.
+    pub fn hovered_half(&self, point: Point) -> Option<SlotHalf> {
+        let geometry = self
+            .hover_index
+            .and_then(|i| self.slot_geometries.get(i))?
+            .as_ref()?;
+        let (dx, dy) = (point.x - geometry.center.x, point.y - geometry.center.y);
+        (dx.hypot(dy) <= geometry.radius).then_some(if dx < 0.0 {
+            SlotHalf::Left
+        } else {
+            SlotHalf::Right
+        })
+    }
 
-                let distance = MENU_RADIUS * self.scale_factor;
-                // padding
-                let half_angle = Radians::atan(g.radius * 1.3 / distance);
+    /// Hovers a subslot in place of any slot. Only a click focuses its window; passing over
+    /// it never does.
+    pub fn select_subslot(&mut self, index: usize) {
+        self.subslot_hover = Some(index);
+        self.hover_index = None;
+        self.activation_pending = false;
+    }
 
-                let start = center_angle - half_angle;
-                let end = center_angle + half_angle;
+    /// Hovers the slot a click past the outer radius lands on, as if the cursor had crossed
+    /// there, and returns the band it's in. Clicks short of the outer radius return `None`.
+    pub fn hover_for_click(&mut self, point: Point) -> Option<Band> {
+        let band = self.band_at(self.distance_from_center(point));
+        if !matches!(band, Band::Near | Band::Far) {
+            return None;
+        }
+        let index = self.find_nearest_slot(point)?;
+        self.hover_index = Some(index);
+        // the crossing counts as made
+        self.cursor_band = band;
+        self.activation_pending = false;
+        Some(band)
+    }
 
-                let mut block_intervals = Vec::new();
+    /// Hovers the slot nearest to a tap by angle, ignoring the activation distance.
+    /// Taps in the center dead zone or outside the ring select nothing.
+    pub fn select_at(&mut self, point: Point) -> Option<usize> {
+        let dist = self.distance_from_center(point);
+        self.hover_index = (self.band_at(dist) != Band::Dead && dist <= self.ring_radius())
+            .then(|| self.find_nearest_slot(point))
+            .flatten();
+        self.hover_index
+    }
+
+    /// Hovers the slot at `index` if it can be activated, i.e. it's filled and not broken.
+    pub fn select_index(&mut self, index: usize) -> Option<usize> {
+        let selectable = self.is_selectable(index);
+        if selectable {
+            self.hover_index = Some(index);
+        }
+        selectable.then_some(index)
+    }
+
+    fn is_selectable(&self, index: usize) -> bool {
+        self.is_shown(index) && !self.slots[index].is_broken()
+    }
+
+    /// Whether the slot at `index` takes a place in the ring: it's filled, and running if
+    /// only running apps are shown.
+    fn is_shown(&self, index: usize) -> bool {
+        self.slots.get(index).is_some_and(|s| {
+            s.app.is_some() && (!self.running_only || s.is_running(&self.active_classes))
+        })
+    }
+
+    /// Switches between showing only running apps and every slot.
+    pub fn toggle_running_only(&mut self) {
+        self.running_only = !self.running_only;
+        self.hover_index = None;
+        self.keyboard_index = None;
+        self.recalculate_geometries();
+    }
+
+    /// Moves the keyboard selection to the next slot that can be activated, clockwise or not,
+    /// starting from the hovered slot when nothing is selected yet, or else from North.
+    /// Returns whether the selection changed.
+    pub fn move_keyboard_focus(&mut self, clockwise: bool) -> bool {
+        let (origin, first) = match self.keyboard_index.or(self.hover_index) {
+            Some(index) => (index, 1),
+            None => (0, 0),
+        };
+        let count = self.slots.len();
+        let next = (first..first + count)
+            .map(|step| {
+                if clockwise {
+                    (origin + step) % count
+                } else {
+                    (origin + 2 * count - step) % count
+                }
+            })
+            .find(|&index| self.is_selectable(index));
+        let changed = next.is_some() && next != self.keyboard_index;
+        if next.is_some() {
+            self.keyboard_index = next;
+        }
+        changed
+    }
+
+    fn distance_from_center(&self, cursor: Point) -> f64 {
+        let (dx, dy) = (cursor.x - self.center.x, cursor.y - self.center.y);
+        dx.hypot(dy)
+    }
+
+    /// Drops the slot and subslot hover, e.g. once the pointer has gone elsewhere. Returns
+    /// whether anything was hovered.
+    pub fn reset_hover(&mut self) -> bool {
+        let slot = self.clear_hover().should_redraw;
+        slot | self.subslot_hover.take().is_some()
+    }
+
+    fn clear_hover(&mut self) -> CursorAction {
+        let changed = self.hover_index.is_some();
+        self.hover_index = None;
+        self.cursor_band = Band::Dead;
+        self.activation_pending = false;
+        CursorAction::new(changed, false)
+    }
+
+    fn cursor_angle(&self, cursor: Point) -> f64 {
+        let (dx, dy) = (cursor.x - self.center.x, cursor.y - self.center.y);
+        dy.atan2(dx)
+    }
+
+    fn find_nearest_slot(&self, cursor: Point) -> Option<usize> {
+        let cursor_angle = self.cursor_angle(cursor);
+        let off_by = |i: usize| SlotGeometry::angle_difference(cursor_angle, self.slot_angle(i));
+        // a pie's empty sectors are drawn as gaps, so pointing at one hovers nothing
+        let in_reach =
+            |i: usize| self.drawn_style() == MenuStyle::Circles || off_by(i) <= ANGLE_STEP / 2.0;
+
+        (0..self.slots.len())
+            .filter(|&i| self.is_shown(i) && in_reach(i))
+            .min_by(|&a, &b| off_by(a).total_cmp(&off_by(b)))
+    }
+
+    /// The angle slot `index` sits at: its direction, or where a category ring put it.
+    fn slot_angle(&self, index: usize) -> f64 {
+        match self.slot_geometries.get(index) {
+            Some(Some(geometry)) if self.in_category_ring() => self.cursor_angle(geometry.center),
+            _ => SlotGeometry::angle(index).0,
+        }
+    }
+
+    fn find_nearest_subslot(&self, cursor: Point) -> Option<usize> {
+        let cursor_angle = self.cursor_angle(cursor);
+
+        (0..self.subslots.len()).min_by(|&a, &b| {
+            let angle_a = self.cursor_angle(self.subslots[a].geometry.center);
+            let angle_b = self.cursor_angle(self.subslots[b].geometry.center);
+            SlotGeometry::angle_difference(cursor_angle, angle_a)
+                .total_cmp(&SlotGeometry::angle_difference(cursor_angle, angle_b))
+        })
+    }
+
+    /// Moves the quick-switch selection to the next window, wrapping around.
+    pub fn cycle_subslot(&mut self) {
+        if self.subslots.is_empty() {
+            return;
+        }
+        self.subslot_hover = Some(
+            self.subslot_hover
+                .map_or(0, |i| (i + 1) % self.subslots.len()),
+        );
+    }
+
+    pub fn get_hovered_subslot(&self) -> Option<&SubSlot> {
+        self.subslot_hover.and_then(|idx| self.subslots.get(idx))
+    }
+
+    pub fn get_hovered_slot(&self) -> Option<&Slot> {
+        self.hover_index.and_then(|idx| self.slots.get(idx))
+    }
+
+    pub fn get_hovered_app(&self) -> Option<&AppInfo> {
+        self.get_hovered_slot().and_then(|slot| slot.app.as_ref())
+    }
+
+    pub fn refresh(
+        &mut self,
+        center: Point,
+        active_classes: Vec<WindowClass>,
+        monitor_height: f64,
+    ) {
+        // every show starts at the top level
+        self.leave_all_submenus();
+        self.restore_configured_slots();
+        self.active_classes = active_classes;
+        self.center = center;
+        self.hover_index = None;
+        self.keyboard_index = None;
+        self.cursor_band = Band::Dead;
+        self.last_cursor = None;
+        self.activation_pending = false;
+        self.flash = None;
+        self.shown_at = Some(Instant::now());
+        self.closing_at = None;
+        self.subslot_hover = None;
+        self.labels_visible = false;
+        // a monitor mid-hotplug can report no size, which would shrink the ring to a point
+        if monitor_height > 0.0 && monitor_height.is_finite() {
+            self.scale_factor = monitor_height.max(MIN_MONITOR_HEIGHT) / REFERENCE_HEIGHT;
+        } else {
+            log::warn!(
+                "Ignoring monitor height {}, keeping the previous scale",
+                monitor_height
+            );
+        }
+
+        if let Some(category) = self.category.clone() {
+            let apps = desktop::entries_in_category(&category);
+            if apps.is_empty() {
+                log::warn!("No desktop entries in category '{}'", category);
+            }
+            self.show_category_ring(apps);
+        }
+
+        self.subslots.clear();
+        if self.subslots_enabled() {
+            self.active_monitor = if self.subslots_on_active_monitor {
+                get_active_monitor_id()
+            } else {
+                None
+            };
+            self.rebuild_subslots(get_active_clients());
+        }
+
+        self.recalculate_geometries();
+        self.clamp_diameter();
+    }
+
+    /// Holds the laid-out ring within `min_diameter`/`max_diameter`. The ring's width only
+    /// shows once it's laid out, and it grows with the scale.
+    fn clamp_diameter(&mut self) {
+        if self.min_diameter.is_some() || self.max_diameter.is_some() {
+            let diameter = 2.0 * self.ring_radius();
+            let scale = clamp_scale(
+                self.scale_factor,
+                diameter,
+                self.min_diameter,
+                self.max_diameter,
+            );
+            if scale != self.scale_factor {
+                self.scale_factor = scale;
+                self.recalculate_geometries();
+            }
+        }
+    }
+
+    /// Grows or shrinks the laid-out ring by `factor` on top of the monitor's scale, still
+    /// within the configured diameters. Hit tests follow, as they go by the same scale.
+    pub fn rescale(&mut self, factor: f64) {
+        if factor > 0.0 && factor.is_finite() && factor != 1.0 {
+            self.scale_factor *= factor;
+            self.recalculate_geometries();
+            self.clamp_diameter();
+        }
+    }
+
+    /// Moves the ring's center into a `width` × `height` surface if it's outside, laying the
+    /// ring out again so hit tests follow. Returns whether it moved. A center measured
+    /// against the monitor can be off a surface that doesn't have its final size yet.
+    pub fn clamp_center(&mut self, width: f64, height: f64) -> bool {
+        if width <= 0.0 || height <= 0.0 {
+            return false;
+        }
+        let clamped = Point::new(
+            self.center.x.clamp(0.0, width),
+            self.center.y.clamp(0.0, height),
+        );
+        if clamped == self.center {
+            return false;
+        }
+        self.center = clamped;
+        self.recalculate_geometries();
+        true
+    }
+
+    /// Takes in a fresh client list while the menu stays open: running states and subslots
+    /// follow it, keeping the hovered subslot if its window is still there.
+    pub fn update_clients(&mut self, clients: Vec<ActiveClient>) {
+        self.set_urgent_clients(&clients);
+        self.set_slot_windows(&clients);
+        self.active_classes = clients
+            .iter()
+            .filter(|c| !(self.ignore_special_workspaces && c.is_on_special_workspace()))
+            .flat_map(|c| c.match_classes().cloned())
+            .collect();
+
+        if self.subslots_enabled() {
+            let hovered = self.get_hovered_subslot().map(|s| s.client.address.clone());
+            self.rebuild_subslots(clients);
+            self.subslot_hover = hovered.and_then(|address| {
+                self.subslots
+                    .iter()
+                    .position(|s| s.client.address == address)
+            });
+        }
+        self.recalculate_geometries();
+    }
+
+    /// Gives each slot the addresses of its windows among `clients`, matched as its running
+    /// state is.
+    pub fn set_slot_windows(&mut self, clients: &[ActiveClient]) {
+        let clients: Vec<_> = clients
+            .iter()
+            .filter(|c| !(self.ignore_special_workspaces && c.is_on_special_workspace()))
+            .collect();
+        for slot in &mut self.slots {
+            slot.windows = clients
+                .iter()
+                .filter(|c| slot.has_window_in(c.match_classes()))
+                .map(|c| c.address.clone())
+                .collect();
+        }
+    }
+
+    pub fn set_urgent_clients(&mut self, clients: &[ActiveClient]) {
+        self.urgent_classes = clients
+            .iter()
+            .filter(|c| c.urgent)
+            .flat_map(|c| c.match_classes().cloned())
+            .collect();
+    }
+
+    /// Whether a slot or subslot wants attention, so the menu keeps redrawing to pulse it.
+    pub fn is_pulsing(&self) -> bool {
+        self.animations_enabled
+            && (self.slots.iter().any(|s| s.is_urgent(&self.urgent_classes))
+                || self.subslots.iter().any(|s| s.client.urgent))
+    }
+
+    /// Assigns a subslot and key to each client no slot covers. Icons are reused from the
+    /// current subslots where the class is the same.
+    fn rebuild_subslots(&mut self, clients: Vec<ActiveClient>) {
+        let previous = std::mem::take(&mut self.subslots);
+        // the switch ring is every window, wherever it is
+        let monitor = self.active_monitor.filter(|_| !self.switch_mode);
+        let mut sub_clients: Vec<_> = clients
+            .into_iter()
+            .filter(|c| monitor.is_none() || c.monitor == monitor)
+            .filter(|c| !self.covered_by_slot(c))
+            .filter(|c| !(self.skip_classless_windows && c.class.is_empty()))
+            .collect();
+        if self.group_subslots_by_workspace {
+            // placed in order, so a workspace's windows end up side by side; they get their
+            // keys in that order too
+            sub_clients.sort_by_key(|c| c.workspace_id);
+        }
+
+        for (sc, shortcut) in sub_clients.into_iter().zip(&self.sub_keys) {
+            let pixbuf = match previous.iter().find(|s| s.client.class == sc.class) {
+                Some(existing) => existing.pixbuf.clone(),
+                None => SubSlot::load_icon(&sc),
+            };
+
+            let geometry = SlotGeometry {
+                center: self.center,
+                radius: 0.0,
+                scale: 0.0,
+            };
+
+            self.subslots.push(SubSlot {
+                client: sc,
+                key: *shortcut,
+                geometry,
+                pixbuf,
+            });
+        }
+    }
+
+    /// When grouping by workspace, a label for each run of neighbouring subslots on the same
+    /// workspace: its name, and the point just beyond the middle of the run to center it on.
+    pub fn workspace_labels(&self) -> Vec<(Point, &str)> {
+        if !self.group_subslots_by_workspace {
+            return Vec::new();
+        }
+        let same_workspace =
+            |a: &SubSlot, b: &SubSlot| a.client.workspace_id == b.client.workspace_id;
+        self.subslots
+            .chunk_by(same_workspace)
+            .map(|run| {
+                // averaged as directions, so a run across the left edge doesn't point right
+                let (sin, cos) = run
+                    .iter()
+                    .map(|s| self.cursor_angle(s.geometry.center))
+                    .fold((0.0, 0.0), |(sin, cos), a| (sin + a.sin(), cos + a.cos()));
+                let angle = sin.atan2(cos);
+                let reach = run
+                    .iter()
+                    .map(|s| self.distance_from_center(s.geometry.center) + s.geometry.radius)
+                    .fold(0.0, f64::max)
+                    + (WORKSPACE_LABEL_GAP + WORKSPACE_LABEL_SIZE / 2.0) * self.scale_factor;
+                let at = Point::new(
+                    self.center.x + reach * angle.cos(),
+                    self.center.y + reach * angle.sin(),
+                );
+                (at, run[0].client.workspace_name.as_str())
+            })
+            .collect()
+    }
+
+    fn subslots_enabled(&self) -> bool {
+        (self.show_subslots && !self.in_category_ring()) || self.switch_mode
+    }
+
+    /// Whether a slot already stands for this client, so it gets no subslot. Quick switch
+    /// shows every window.
+    fn covered_by_slot(&self, client: &ActiveClient) -> bool {
+        !self.switch_mode
+            && self
+                .slots
+                .iter()
+                .filter_map(|s| s.app.as_ref())
+                .filter(|app| !app.class.is_empty())
+                .any(|app| {
+                    client
+                        .match_classes()
+                        .any(|class| class.to_lowercase() == app.class.to_lowercase())
+                })
+    }
+
+    /// Explains the subslots: every open client right now, the ones left out because a slot
+    /// covers them, and what the last show actually assigned.
+    pub fn subslot_report(&self) -> SubslotReport {
+        let clients = get_active_clients();
+        let excluded = clients
+            .iter()
+            .filter(|c| self.covered_by_slot(c))
+            .map(|c| c.class.clone())
+            .collect();
+
+        SubslotReport {
+            enabled: self.subslots_enabled(),
+            clients: clients.iter().map(ClientReport::from).collect(),
+            excluded,
+            subslots: self
+                .subslots
+                .iter()
+                .map(|s| SubslotAssignment {
+                    key: s.key,
+                    client: ClientReport::from(&s.client),
+                })
+                .collect(),
+        }
+    }
+
+    /// The radius a flick must cross to reach the last band.
+    fn activation_radius(&self) -> f64 {
+        self.activation
+            .far_radius
+            .unwrap_or(self.activation.outer_radius)
+            .max(self.activation.outer_radius)
+    }
+
+    /// Distance from the center to the outer edge of the furthest slot or subslot, never less
+    /// than the activation distance so a flick can always cross it.
+    pub fn ring_radius(&self) -> f64 {
+        let extent = |g: &SlotGeometry| {
+            (g.center.x - self.center.x).hypot(g.center.y - self.center.y) + g.radius
+        };
+        self.slot_geometries
+            .iter()
+            .flatten()
+            .chain(self.subslots.iter().map(|s| &s.geometry))
+            .map(extent)
+            .fold(self.activation_radius() * self.scale_factor, f64::max)
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.slots.iter().any(|s| s.loading)
+    }
+
+    /// The icon files of every slot still waiting for its icon, nested and put-aside slots
+    /// included, for [`IconData::load`] to decode in the background.
+    pub fn pending_icons(&self) -> Vec<PathBuf> {
+        fn collect(slots: &[Slot], paths: &mut Vec<PathBuf>) {
+            for slot in slots {
+                if slot.loading
+                    && let Some(path) = slot.icon_path()
+                    && !paths.iter().any(|p| p == path)
+                {
+                    paths.push(path.to_path_buf());
+                }
+                collect(&slot.children, paths);
+            }
+        }
+        let mut paths = Vec::new();
+        collect(&self.slots, &mut paths);
+        for slots in self.configured_slots.iter() {
+            collect(slots, &mut paths);
+        }
+        for parent in &self.parent_menus {
+            collect(&parent.slots, &mut paths);
+        }
+        paths
+    }
+
+    /// Hands the decoded icons to the slots waiting for them. One that couldn't be decoded
+    /// still ends the wait, and the slot shows its name instead.
+    pub fn set_icons(&mut self, icons: Vec<(PathBuf, Option<IconData>)>) {
+        fn supply(slots: &mut [Slot], icons: &HashMap<PathBuf, Option<Pixbuf>>) {
+            for slot in slots {
+                let icon = slot.icon_path().and_then(|path| icons.get(path)).cloned();
+                if slot.loading
+                    && let Some(pixbuf) = icon
+                {
+                    slot.set_pixbuf(pixbuf);
+                }
+                supply(&mut slot.children, icons);
+            }
+        }
+        let icons: HashMap<_, _> = icons
+            .into_iter()
+            .map(|(path, icon)| (path, icon.map(IconData::into_pixbuf)))
+            .collect();
+        supply(&mut self.slots, &icons);
+        if let Some(slots) = &mut self.configured_slots {
+            supply(slots, &icons);
+        }
+        for parent in &mut self.parent_menus {
+            supply(&mut parent.slots, &icons);
+        }
+    }
+
+    pub fn reload_icons(&mut self) {
+        self.slots.iter_mut().for_each(Slot::reload_icon);
+        for subslot in &mut self.subslots {
+            subslot.pixbuf = SubSlot::load_icon(&subslot.client);
+        }
+    }
+
+    fn filled_slot_indices(&self) -> Vec<usize> {
+        (0..self.slots.len())
+            .filter(|&i| self.is_shown(i))
+            .collect()
+    }
+
+    fn recalculate_geometries(&mut self) {
+        if self.switch_mode {
+            self.slot_geometries = vec![None; self.slots.len()];
+            let total = self.subslots.len();
+            for (i, subslot) in self.subslots.iter_mut().enumerate() {
+                subslot.geometry =
+                    SlotGeometry::calculate_ring(i, total, self.center, self.scale_factor);
+            }
+            return;
+        }
+        if self.in_category_ring() {
+            let total = self.slots.len();
+            self.slot_geometries = (0..total)
+                .map(|i| {
+                    Some(SlotGeometry::calculate_ring(
+                        i,
+                        total,
+                        self.center,
+                        self.scale_factor,
+                    ))
+                })
+                .collect();
+            return;
+        }
+
+        let filled_indices = self.filled_slot_indices();
+        self.slot_geometries = self.calculate_main_slots(&filled_indices);
+
+        let segments = self.find_free_segments();
+        self.distribute_subslots(&segments);
+    }
+
+    fn calculate_main_slots(&self, filled_indices: &[usize]) -> Vec<Option<SlotGeometry>> {
+        let counts: Vec<u64> = self.slots.iter().map(|s| self.usage_count(s)).collect();
+        let max_count = counts.iter().copied().max().unwrap_or_default();
+
+        (0..self.slots.len())
+            .map(|i| {
+                filled_indices.contains(&i).then(|| {
+                    let mut geometry = SlotGeometry::calculate(
+                        i,
+                        filled_indices,
+                        self.center,
+                        self.scale_factor,
+                        self.slot_scaling,
+                    );
+                    geometry.radius *= usage_factor(counts[i], max_count);
+                    geometry
+                })
+            })
+            .collect()
+    }
+
+    fn usage_count(&self, slot: &Slot) -> u64 {
+        if !self.weight_by_usage {
+            return 0;
+        }
+        slot.app
+            .as_ref()
+            .and_then(|app| self.usage_counts.get(&app.class.to_lowercase()))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn find_free_segments(&self) -> Vec<AngularSegment> {
+        let mut free_segments = vec![AngularSegment::new(-PI, PI)];
+
+        for (i, geom) in self.slot_geometries.iter().enumerate() {
+            if let Some(g) = geom {
+                let center_angle = SlotGeometry::angle(i).normalize();
+
+                let distance = MENU_RADIUS * self.scale_factor;
+                // padding
+                let half_angle = Radians::atan(g.radius * 1.3 / distance);
+
+                let start = center_angle - half_angle;
+                let end = center_angle + half_angle;
+
+                let mut block_intervals = Vec::new();
 
                 if start.0 < -PI {
                     // wraps past -PI
@@ -464,66 +1862,252 @@ impl State {
         } else {
             free_segments.to_vec()
         };
-        let valid_total_length: f64 = segments.iter().map(|s| s.len()).sum();
+        // the counts add up to `subslot_count`, so every subslot gets a place of its own
+        let final_counts = allocate_subslots(&segments, subslot_count);
 
-        // calculate ideal fractional counts
-        let allocations: Vec<f64> = segments
-            .iter()
-            .map(|s| (s.len() / valid_total_length) * subslot_count as f64)
-            .collect();
+        let mut subslot_iter = self.subslots.iter_mut();
 
-        // initial floor allocation
-        let mut final_counts: Vec<usize> = allocations.iter().map(|f| f.floor() as usize).collect();
-        let current_total: usize = final_counts.iter().sum();
-
-        // distribute remainder to segments with largest fractional parts
-        let remainder = subslot_count - current_total;
-        if remainder > 0 {
-            let mut indices: Vec<usize> = (0..segments.len()).collect();
-            indices.sort_by(|&a, &b| {
-                let frac_a = allocations[a].fract();
-                let frac_b = allocations[b].fract();
-                frac_b
-                    .partial_cmp(&frac_a)
-                    .unwrap_or(std::cmp::Ordering::Equal)
-            });
+        for (seg, &count) in segments.iter().zip(&final_counts) {
+            if count == 0 {
+                continue;
+            }
+
+            let step = seg.len() / count as f64;
 
-            for i in 0..remainder {
-                final_counts[indices[i]] += 1;
+            for (k, subslot) in subslot_iter.by_ref().take(count).enumerate() {
+                // center within the allocated step
+                let angle = seg.start + Radians(step * (k as f64 + 0.5));
+                subslot.geometry = SlotGeometry::from_angle(angle, self.center, self.scale_factor);
             }
         }
+    }
+}
 
-        let mut subslot_iter = self.subslots.iter_mut();
+/// A themed icon for what `xdg-open` will show: a browser for URLs, a folder for directories,
+/// a generic document otherwise. Guessing the mime type isn't worth a lookup per slot.
+fn open_icon_name(target: &str) -> &'static str {
+    let lower = target.to_ascii_lowercase();
+    if lower.starts_with("http://") || lower.starts_with("https://") {
+        "web-browser"
+    } else if lower.starts_with("mailto:") {
+        "mail-send"
+    } else if target.ends_with('/') || std::path::Path::new(target).is_dir() {
+        "folder"
+    } else {
+        "text-x-generic"
+    }
+}
 
-        for (i, seg) in segments.iter().enumerate() {
-            let count = final_counts[i];
-            if count == 0 {
+/// Radius multiplier for a slot launched `count` times when the busiest in the ring was launched
+/// `max_count` times. The square root keeps one dominant app from dwarfing the rest.
+fn usage_factor(count: u64, max_count: u64) -> f64 {
+    if max_count == 0 {
+        return 1.0;
+    }
+    1.0 + USAGE_MAX_GROWTH * (count as f64 / max_count as f64).sqrt()
+}
+
+/// Places each config slot at its direction, leaving the rest empty.
+fn build_ring(configs: &[SlotConfig]) -> Vec<Slot> {
+    let mut slots = vec![Slot::empty(); SLOT_COUNT];
+
+    configs
+        .iter()
+        .filter(|cfg| cfg.enabled)
+        .filter_map(|cfg| cfg.direction.map(|dir| (dir, cfg)))
+        .for_each(|(dir, cfg)| {
+            slots[dir.as_index()] = Slot::from_config(cfg);
+        });
+
+    slots
+}
+
+/// The setup slot's icon. The slot isn't a real app, so no theme has an icon for it.
+const SETUP_ICON: &[u8] = include_bytes!("setup.svg");
+
+fn load_setup_icon() -> Option<Pixbuf> {
+    use gdk_pixbuf::prelude::PixbufLoaderExt;
+
+    let loader = PixbufLoader::new();
+    loader.set_size(ICON_SIZE, ICON_SIZE);
+    loader
+        .write(SETUP_ICON)
+        .and_then(|()| loader.close())
+        .inspect_err(|e| log::warn!("Failed to load the setup icon: {}", e))
+        .ok()?;
+    loader.pixbuf()
+}
+
+/// Loads a center icon given as an image path or a theme icon name.
+fn load_center_icon(icon: &str) -> Option<Pixbuf> {
+    let path = PathBuf::from(icon);
+    let path = if path.is_file() {
+        path
+    } else {
+        icon::find_icon_path(&IconName::new(icon))?
+    };
+    Pixbuf::from_file_at_scale(&path, ICON_SIZE, ICON_SIZE, true)
+        .inspect_err(|e| log::warn!("Failed to load center icon '{}': {}", icon, e))
+        .ok()
+}
+
+/// Whether an icon reads as one color on transparency, like symbolic and tray-style icons.
+fn looks_monochrome(pixbuf: &Pixbuf) -> bool {
+    pixbuf.has_alpha()
+        && pixels_monochrome(
+            &pixbuf.read_pixel_bytes(),
+            pixbuf.width() as usize,
+            pixbuf.height() as usize,
+            pixbuf.rowstride() as usize,
+            pixbuf.n_channels() as usize,
+        )
+}
+
+/// Nearly all visible pixels of packed RGBA rows are gray, whatever their shade; a few
+/// colored pixels are allowed for antialiasing.
+fn pixels_monochrome(
+    pixels: &[u8],
+    width: usize,
+    height: usize,
+    rowstride: usize,
+    channels: usize,
+) -> bool {
+    const VISIBLE_ALPHA: u8 = 32;
+    const MAX_SPREAD: u8 = 24;
+    const MAX_COLORED_FRACTION: f64 = 0.05;
+
+    let (mut visible, mut colored) = (0usize, 0usize);
+    for row in pixels.chunks(rowstride).take(height) {
+        for px in row.chunks_exact(channels).take(width) {
+            let [r, g, b, a] = [px[0], px[1], px[2], px[3]];
+            if a <= VISIBLE_ALPHA {
                 continue;
             }
+            visible += 1;
+            if r.max(g).max(b) - r.min(g).min(b) > MAX_SPREAD {
+                colored += 1;
+            }
+        }
+    }
+    visible > 0 && (colored as f64) <= visible as f64 * MAX_COLORED_FRACTION
+}
 
-            let step = seg.len() / count as f64;
+/// A single sine hump over [`FLASH_DURATION_MS`], zero before and after.
+fn flash_pulse(elapsed: Duration) -> f64 {
+    let t = elapsed.as_secs_f64() * 1000.0 / FLASH_DURATION_MS as f64;
+    if t >= 1.0 { 0.0 } else { (t * PI).sin() }
+}
 
-            for k in 0..count {
-                if let Some(subslot) = subslot_iter.next() {
-                    // center within the allocated step
-                    let angle = seg.start + Radians(step * (k as f64 + 0.5));
-                    subslot.geometry =
-                        SlotGeometry::from_angle(angle, self.center, self.scale_factor);
-                }
+/// Eases out, so subslots leave the center quickly and settle into place.
+fn fan_out_ease(elapsed: Duration, duration: Duration) -> f64 {
+    if duration.is_zero() {
+        return 1.0;
+    }
+    let t = (elapsed.as_secs_f64() / duration.as_secs_f64()).min(1.0);
+    1.0 - (1.0 - t).powi(3)
+}
+
+/// The scale at which a ring `diameter` wide at `scale` fits within `min` and `max`. `max`
+/// wins when the two disagree.
+fn clamp_scale(scale: f64, diameter: f64, min: Option<f64>, max: Option<f64>) -> f64 {
+    if diameter <= 0.0 {
+        return scale;
+    }
+    let mut clamped = diameter;
+    if let Some(min) = min {
+        clamped = clamped.max(min);
+    }
+    if let Some(max) = max {
+        clamped = clamped.min(max);
+    }
+    scale * clamped / diameter
+}
+
+/// Eases in, the reverse of [`fan_out_ease`], so the menu lingers and then drops away.
+fn close_ease(elapsed: Duration, duration: Duration) -> f64 {
+    1.0 - fan_out_ease(duration.saturating_sub(elapsed), duration)
+}
+
+/// The app a `hypraise` invocation raises: the first positional argument after the binary,
+/// skipping `-c`/`-e` and their values. Subcommands like `show` come back as queries too, but
+/// never match a slot.
+fn hypraise_query(exec: &str) -> Option<&str> {
+    let mut args = exec
+        .split_whitespace()
+        .skip_while(|arg| !(*arg == "hypraise" || arg.ends_with("/hypraise")))
+        .skip(1);
+    while let Some(arg) = args.next() {
+        if arg.starts_with('-') {
+            if !arg.contains('=') {
+                args.next();
             }
+        } else {
+            return Some(arg);
         }
+    }
+    None
+}
 
-        for subslot in subslot_iter {
-            subslot.geometry =
-                SlotGeometry::from_angle(Radians(0.0), self.center, self.scale_factor);
+/// Splits `count` subslots across `segments` proportionally to their length (largest remainder).
+/// Ties on the remainder go to the segment starting at the smaller angle, so equal segments
+/// always receive the same allocation and subslots don't jump between refreshes. The counts
+/// always add up to `count` unless there are no segments at all.
+fn allocate_subslots(segments: &[AngularSegment], count: usize) -> Vec<usize> {
+    let total_length: f64 = segments.iter().map(|s| s.len().max(0.0)).sum();
+    // with no length to go by, segments share equally rather than dividing by zero
+    let share = |s: &AngularSegment| {
+        if total_length > 0.0 {
+            s.len().max(0.0) / total_length
+        } else {
+            1.0 / segments.len() as f64
+        }
+    };
+
+    // calculate ideal fractional counts
+    let allocations: Vec<f64> = segments.iter().map(|s| share(s) * count as f64).collect();
+
+    // initial floor allocation
+    let mut final_counts: Vec<usize> = allocations.iter().map(|f| f.floor() as usize).collect();
+    let current_total: usize = final_counts.iter().sum();
+
+    // distribute remainder to segments with largest fractional parts
+    let remainder = count.saturating_sub(current_total);
+    if remainder > 0 {
+        // quantized so that float noise between "equal" segments counts as a tie
+        let remainder_key = |i: usize| (allocations[i].fract() * 1e6).round() as i64;
+        let mut indices: Vec<usize> = (0..segments.len()).collect();
+        indices.sort_by(|&a, &b| {
+            remainder_key(b)
+                .cmp(&remainder_key(a))
+                .then(segments[a].start.0.total_cmp(&segments[b].start.0))
+        });
+
+        // float error can leave more over than there are segments, so go round again
+        for &i in indices.iter().cycle().take(remainder) {
+            final_counts[i] += 1;
         }
     }
+    final_counts
+}
+
+/// How far out the cursor is, see [`ActivationConfig`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Band {
+    #[default]
+    Dead,
+    Hover,
+    /// Past `outer_radius`
+    Near,
+    /// Past `far_radius`, when one is configured
+    Far,
 }
 
 #[derive(Debug, Clone, Copy, Default)]
 pub struct CursorAction {
     pub should_redraw: bool,
     pub should_activate: bool,
+    /// The band the cursor is in, which picks the action when activating
+    pub band: Band,
 }
 
 impl CursorAction {
@@ -531,6 +2115,1081 @@ impl CursorAction {
         Self {
             should_redraw,
             should_activate,
+            band: Band::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gui::menu::INNER_RADIUS;
+    use hypraise::wm::{Address, ClientTitle};
+
+    fn slot(name: &str, class: &str, exec: &str) -> Slot {
+        // built directly so no pixbuf is loaded
+        Slot {
+            app: Some(AppInfo::from_parts(
+                AppName::new(name),
+                PathBuf::new(),
+                WindowClass::new(class),
+                ExecCommand::new(exec),
+            )),
+            pixbuf: None,
+            monochrome: false,
+            loading: false,
+            toggle: false,
+            single_instance: false,
+            colors: SlotColors::default(),
+            children: Vec::new(),
+            open: None,
+            left_exec: None,
+            right_exec: None,
+            windows: Vec::new(),
+            config: None,
+        }
+    }
+
+    fn client(address: &str, class: &str) -> ActiveClient {
+        ActiveClient {
+            address: Address::new(address),
+            class: WindowClass::new(class),
+            initial_class: WindowClass::new(class),
+            title: ClientTitle::new(""),
+            workspace_id: 1,
+            workspace_name: "1".to_string(),
+            monitor: None,
+            focused: false,
+            urgent: false,
+        }
+    }
+
+    fn state_with(filled: &[(usize, &str)], center: Point) -> State {
+        let mut slots = vec![Slot::empty(); SLOT_COUNT];
+        for &(idx, name) in filled {
+            slots[idx] = slot(name, name, name);
+        }
+        State::new(slots, center, Vec::new(), 1.0, false)
+    }
+
+    #[test]
+    fn test_select_at_picks_nearest_slot_by_angle() {
+        let center = Point::new(500.0, 500.0);
+        let mut state = state_with(&[(0, "north"), (2, "east")], center);
+
+        // inside the ring, well short of OUTER_RADIUS
+        assert_eq!(state.select_at(Point::new(500.0, 420.0)), Some(0));
+        assert_eq!(state.select_at(Point::new(580.0, 510.0)), Some(2));
+        // center dead zone cancels
+        assert_eq!(state.select_at(Point::new(505.0, 495.0)), None);
+        assert_eq!(state.hover_index, None);
+        // far outside the ring
+        assert_eq!(state.select_at(Point::new(1500.0, 500.0)), None);
+    }
+
+    #[test]
+    fn test_flash_pulse() {
+        let half = Duration::from_millis(FLASH_DURATION_MS / 2);
+        assert_eq!(flash_pulse(Duration::ZERO), 0.0);
+        assert!((flash_pulse(half) - 1.0).abs() < 1e-6);
+        assert_eq!(flash_pulse(Duration::from_millis(FLASH_DURATION_MS)), 0.0);
+
+        let mut state = state_with(&[(0, "north"), (2, "east")], Point::default());
+        state.flash = Some((0, Instant::now() - half));
+        assert!(state.flash_amount(0) > 0.5);
+        assert_eq!(state.flash_amount(2), 0.0);
+    }
+
+    #[test]
+    fn test_refresh_ignores_bad_monitor_height() {
+        let mut state = state_with(&[(0, "north")], Point::default());
+        state.refresh(Point::default(), Vec::new(), 2160.0);
+        assert_eq!(state.scale_factor, 1.5);
+
+        state.refresh(Point::default(), Vec::new(), 0.0);
+        assert_eq!(state.scale_factor, 1.5);
+        state.refresh(Point::default(), Vec::new(), f64::NAN);
+        assert_eq!(state.scale_factor, 1.5);
+
+        state.refresh(Point::default(), Vec::new(), 10.0);
+        assert_eq!(state.scale_factor, MIN_MONITOR_HEIGHT / REFERENCE_HEIGHT);
+    }
+
+    #[test]
+    fn test_fan_out_ease() {
+        let duration = Duration::from_millis(150);
+        assert_eq!(fan_out_ease(Duration::ZERO, duration), 0.0);
+        assert!(fan_out_ease(duration / 2, duration) > 0.5);
+        assert_eq!(fan_out_ease(duration, duration), 1.0);
+        assert_eq!(fan_out_ease(duration * 2, duration), 1.0);
+        // off means already in place
+        assert_eq!(fan_out_ease(Duration::ZERO, Duration::ZERO), 1.0);
+
+        let mut state = state_with(&[(0, "north")], Point::default());
+        state.subslot_fan_out = duration;
+        state.shown_at = Some(Instant::now());
+        assert!(state.fan_out_progress() < 1.0);
+        state.animations_enabled = false;
+        assert_eq!(state.fan_out_progress(), 1.0);
+    }
+
+    #[test]
+    fn test_diameter_clamps_scale() {
+        assert_eq!(clamp_scale(2.0, 1000.0, None, Some(500.0)), 1.0);
+        assert_eq!(clamp_scale(0.5, 200.0, Some(400.0), None), 1.0);
+        assert_eq!(clamp_scale(1.5, 600.0, Some(400.0), Some(800.0)), 1.5);
+        assert_eq!(clamp_scale(1.0, 600.0, Some(900.0), Some(300.0)), 0.5);
+
+        let mut state = state_with(&[(0, "north"), (4, "south")], Point::default());
+        state.refresh(Point::default(), Vec::new(), 2880.0);
+        let unclamped = 2.0 * state.ring_radius();
+        state.max_diameter = Some(unclamped / 2.0);
+        state.refresh(Point::default(), Vec::new(), 2880.0);
+        assert!((state.scale_factor - 1.0).abs() < 1e-9);
+        assert!((2.0 * state.ring_radius() - unclamped / 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_close_progress() {
+        let duration = Duration::from_millis(CLOSE_DURATION_MS);
+        assert_eq!(close_ease(Duration::ZERO, duration), 0.0);
+        assert!(close_ease(duration / 2, duration) < 0.5);
+        assert_eq!(close_ease(duration * 2, duration), 1.0);
+
+        let mut state = state_with(&[(0, "north")], Point::default());
+        assert_eq!(state.close_progress(), 0.0);
+        state.closing_at = Some(Instant::now() - duration);
+        assert_eq!(state.close_progress(), 1.0);
+        // showing again calls the close off
+        state.refresh(Point::default(), Vec::new(), 1440.0);
+        assert_eq!(state.closing_at, None);
+        assert_eq!(state.close_progress(), 0.0);
+    }
+
+    #[test]
+    fn test_select_index_skips_empty_and_broken() {
+        let mut state = state_with(&[(0, "north"), (3, "southeast")], Point::default());
+        state.slots[5] = slot("Broken", "broken", "");
+
+        assert_eq!(state.select_index(3), Some(3));
+        assert_eq!(state.hover_index, Some(3));
+        // empty, broken and out of range keep the current hover
+        assert_eq!(state.select_index(1), None);
+        assert_eq!(state.select_index(5), None);
+        assert_eq!(state.select_index(SLOT_COUNT), None);
+        assert_eq!(state.hover_index, Some(3));
+    }
+
+    #[test]
+    fn test_update_cursor_transitions() {
+        let center = Point::new(500.0, 500.0);
+        let mut state = state_with(&[(0, "north"), (2, "east")], center);
+        // every radius doubles, so the bands are twice as far out
+        state.scale_factor = 2.0;
+        let north = |dist: f64| Point::new(500.0, 500.0 - dist * 2.0);
+        let east = |dist: f64| Point::new(500.0 + dist * 2.0, 500.0);
+        let between = (INNER_RADIUS + OUTER_RADIUS) / 2.0;
+
+        // within the unscaled outer radius, but scaled it's still short of it
+        let action = state.update_cursor(Point::new(500.0, 500.0 - OUTER_RADIUS));
+        assert_eq!(action.band, Band::Hover);
+        assert!(action.should_redraw && !action.should_activate);
+        assert_eq!(state.hover_index, Some(0));
+
+        // the dead zone clears the hover, redrawing only if something was hovered
+        let action = state.update_cursor(north(INNER_RADIUS / 2.0));
+        assert!(action.should_redraw && !action.should_activate);
+        assert_eq!(state.hover_index, None);
+        assert!(!state.update_cursor(north(INNER_RADIUS / 2.0)).should_redraw);
+
+        let action = state.update_cursor(north(between));
+        assert!(action.should_redraw && !action.should_activate);
+        assert_eq!(state.hover_index, Some(0));
+        assert!(!state.update_cursor(north(between + 1.0)).should_redraw);
+
+        // moving over to another slot redraws
+        let action = state.update_cursor(east(between));
+        assert!(action.should_redraw && !action.should_activate);
+        assert_eq!(state.hover_index, Some(2));
+
+        let action = state.update_cursor(east(OUTER_RADIUS + 10.0));
+        assert_eq!(action.band, Band::Near);
+        assert!(action.should_redraw && action.should_activate);
+        assert_eq!(state.hover_index, Some(2));
+    }
+
+    #[test]
+    fn test_click_past_outer_radius() {
+        let center = Point::new(500.0, 500.0);
+        let mut state = state_with(&[(0, "north"), (2, "east")], center);
+        state.activation.far_radius = Some(250.0);
+
+        assert_eq!(state.hover_for_click(Point::new(500.0, 450.0)), None);
+        assert_eq!(
+            state.hover_for_click(Point::new(650.0, 500.0)),
+            Some(Band::Near)
+        );
+        assert_eq!(state.hover_index, Some(2));
+        assert_eq!(
+            state.hover_for_click(Point::new(500.0, 200.0)),
+            Some(Band::Far)
+        );
+        assert_eq!(state.hover_index, Some(0));
+        // moving on within the band doesn't fire the same crossing again
+        assert!(
+            !state
+                .update_cursor(Point::new(500.0, 190.0))
+                .should_activate
+        );
+
+        let mut empty = state_with(&[], center);
+        assert_eq!(empty.hover_for_click(Point::new(650.0, 500.0)), None);
+    }
+
+    #[test]
+    fn test_keyboard_focus_skips_unselectable_slots() {
+        let mut state = state_with(&[(0, "north"), (2, "east"), (6, "west")], Point::default());
+        state.slots[2].app.as_mut().unwrap().exec = ExecCommand::new("").into();
+
+        // the first press lands on North itself
+        assert!(state.move_keyboard_focus(true));
+        assert_eq!(state.keyboard_index, Some(0));
+        // the broken East slot is skipped
+        assert!(state.move_keyboard_focus(true));
+        assert_eq!(state.keyboard_index, Some(6));
+        assert!(state.move_keyboard_focus(true));
+        assert_eq!(state.keyboard_index, Some(0));
+        assert!(state.move_keyboard_focus(false));
+        assert_eq!(state.keyboard_index, Some(6));
+        // the pointer's hover is left alone
+        assert_eq!(state.hover_index, None);
+
+        state.refresh(Point::default(), Vec::new(), 1440.0);
+        assert_eq!(state.keyboard_index, None);
+        state.hover_index = Some(6);
+        assert!(state.move_keyboard_focus(false));
+        assert_eq!(state.keyboard_index, Some(0));
+
+        let mut empty = state_with(&[], Point::default());
+        assert!(!empty.move_keyboard_focus(true));
+        assert_eq!(empty.keyboard_index, None);
+    }
+
+    #[test]
+    fn test_pie_hovers_only_filled_sectors() {
+        let center = Point::new(500.0, 500.0);
+        let mut state = state_with(&[(0, "north"), (2, "east")], center);
+        let south_east = Point::new(600.0, 600.0);
+
+        state.update_cursor(south_east);
+        assert_eq!(state.hover_index, Some(2));
+
+        state.style = MenuStyle::Pie;
+        state.update_cursor(south_east);
+        assert_eq!(state.hover_index, None);
+        state.update_cursor(Point::new(600.0, 520.0));
+        assert_eq!(state.hover_index, Some(2));
+
+        // content sits inside its own sector, clear of the dead zone
+        let sector = SlotGeometry::sector(2, center, 1.0);
+        let distance = sector.center.x - center.x;
+        assert!((sector.center.y - center.y).abs() < 1e-9);
+        assert!(distance - sector.radius > INNER_RADIUS);
+        assert!(distance + sector.radius < PIE_OUTER_RADIUS);
+    }
+
+    #[test]
+    fn test_bands_activate_once_on_entry() {
+        let center = Point::new(500.0, 500.0);
+        let mut state = state_with(&[(0, "north")], center);
+        state.activation.far_radius = Some(250.0);
+        let at = |dist: f64| Point::new(500.0, 500.0 - dist);
+
+        let action = state.update_cursor(at(100.0));
+        assert_eq!(action.band, Band::Hover);
+        assert!(!action.should_activate);
+
+        let action = state.update_cursor(at(150.0));
+        assert_eq!(action.band, Band::Near);
+        assert!(action.should_activate);
+        // staying in the band doesn't fire again
+        assert!(!state.update_cursor(at(200.0)).should_activate);
+
+        let action = state.update_cursor(at(300.0));
+        assert_eq!(action.band, Band::Far);
+        assert!(action.should_activate);
+
+        // the dead zone resets, so the next flick fires again
+        assert_eq!(state.update_cursor(at(10.0)).band, Band::Dead);
+        assert!(state.update_cursor(at(150.0)).should_activate);
+    }
+
+    #[test]
+    fn test_fast_crossing_waits_until_settled() {
+        let center = Point::new(500.0, 500.0);
+        let mut state = state_with(&[(0, "north"), (2, "east")], center);
+        state.activation.max_velocity = Some(1000.0);
+        let start = Instant::now();
+        let after = |ms: u64| start + Duration::from_millis(ms);
+
+        state.update_cursor_at(Point::new(500.0, 420.0), after(0));
+        // 100px in 10ms is 10000px/s
+        let action = state.update_cursor_at(Point::new(500.0, 320.0), after(10));
+        assert_eq!(action.band, Band::Near);
+        assert!(!action.should_activate);
+        assert_eq!(state.hover_index, Some(0));
+
+        // settling inside the band fires the crossing that was held back
+        let action = state.update_cursor_at(Point::new(502.0, 318.0), after(100));
+        assert!(action.should_activate);
+        assert!(
+            !state
+                .update_cursor_at(Point::new(502.0, 318.0), after(200))
+                .should_activate
+        );
+
+        // slow enough crossings fire straight away
+        state.update_cursor_at(center, after(300));
+        state.update_cursor_at(Point::new(560.0, 500.0), after(400));
+        assert!(
+            state
+                .update_cursor_at(Point::new(640.0, 500.0), after(500))
+                .should_activate
+        );
+    }
+
+    #[test]
+    fn test_touch_mode_never_activates_on_motion() {
+        let center = Point::new(500.0, 500.0);
+        let mut state = state_with(&[(0, "north")], center);
+        state.input_mode = InputMode::Touch;
+
+        let action = state.update_cursor(Point::new(500.0, 100.0));
+        assert!(!action.should_activate);
+        assert_eq!(state.hover_index, Some(0));
+    }
+
+    #[test]
+    fn test_keybind_hints_match_hypraise_binds() {
+        let mut slots = vec![Slot::empty(); SLOT_COUNT];
+        slots[0] = slot("Zen Browser", "zen", "zen-browser");
+        slots[2] = slot("Ghostty", "com.mitchellh.ghostty", "ghostty");
+        slots[4] = slot("Dolphin", "org.kde.dolphin", "dolphin");
+        let mut state = State::new(slots, Point::default(), Vec::new(), 1.0, false);
+
+        let bind = |modmask, key: &str, dispatcher: &str, arg: &str| KeyBind {
+            modmask,
+            key: key.into(),
+            dispatcher: dispatcher.into(),
+            arg: arg.into(),
+        };
+        state.set_keybind_hints(&[
+            bind(64, "b", "exec", "hypraise zen"),
+            bind(
+                64,
+                "Return",
+                "exec",
+                "~/.cargo/bin/hypraise -c ghostty Ghostty",
+            ),
+            // not exec, so not a launch
+            bind(64, "d", "workspace", "hypraise dolphin"),
+            bind(64, "space", "exec", "hypraise show"),
+        ]);
+
+        assert_eq!(state.keybind_hint(0), Some("SUPER+B"));
+        assert_eq!(state.keybind_hint(2), Some("SUPER+RETURN"));
+        assert_eq!(state.keybind_hint(4), None);
+        assert_eq!(state.keybind_hint(1), None);
+    }
+
+    #[test]
+    fn test_submenu_navigation() {
+        let center = Point::new(500.0, 500.0);
+        let mut state = state_with(&[(0, "files")], center);
+        let mut group = slot("Dev", "", "");
+        group.children = vec![Slot::empty(); SLOT_COUNT];
+        group.children[2] = slot("code", "code", "code");
+        group.children[6] = slot("ghostty", "ghostty", "ghostty");
+        state.set_slot(Direction::South, group);
+        assert!(!state.slots[4].is_broken());
+
+        // not a group
+        assert!(!state.enter_submenu(0, center));
+
+        // opened where the cursor flicked out, so it starts in the dead zone
+        let cursor = Point::new(500.0, 700.0);
+        state.hover_index = Some(4);
+        assert!(state.enter_submenu(4, cursor));
+        assert!(state.in_submenu());
+        assert_eq!(state.center, cursor);
+        assert_eq!(state.hover_index, None);
+        assert_eq!(state.filled_slot_indices(), vec![2, 6]);
+        assert!(state.in_dead_zone(cursor));
+
+        // the ring above stays where it was drawn, behind the submenu
+        assert_eq!(state.submenu_depth(), 1);
+        let ring = state.parent_rings().next().unwrap();
+        assert_eq!(ring.opened, 4);
+        assert_eq!(ring.center, center);
+        assert_eq!(ring.slots[0].app.as_ref().unwrap().name.as_str(), "files");
+        let geometry = ring.geometries[4].as_ref().unwrap();
+        assert!(geometry.center.y > center.y);
+
+        assert!(state.leave_submenu());
+        assert_eq!(state.submenu_depth(), 0);
+        assert!(!state.in_submenu());
+        assert_eq!(state.center, cursor);
+        assert_eq!(state.filled_slot_indices(), vec![0, 4]);
+        assert!(!state.leave_submenu());
+
+        // reloading slots drops back to the top level
+        assert!(state.enter_submenu(4, cursor));
+        state.set_slots(vec![Slot::empty(); SLOT_COUNT]);
+        assert!(!state.in_submenu());
+    }
+
+    #[test]
+    fn test_usage_weighting_grows_busy_slots() {
+        let mut state = state_with(
+            &[(0, "zen"), (2, "ghostty"), (4, "dolphin")],
+            Point::default(),
+        );
+        let radii = |state: &State| -> Vec<f64> {
+            [0, 2, 4]
+                .map(|i| state.slot_geometries[i].as_ref().unwrap().radius)
+                .to_vec()
+        };
+        let uniform = radii(&state);
+        state.usage_counts = HashMap::from([("zen".into(), 100), ("ghostty".into(), 25)]);
+
+        // ignored until enabled
+        state.recalculate_geometries();
+        assert_eq!(radii(&state), uniform);
+
+        state.weight_by_usage = true;
+        state.recalculate_geometries();
+        let weighted = radii(&state);
+        assert!((weighted[0] - uniform[0] * (1.0 + USAGE_MAX_GROWTH)).abs() < 1e-9);
+        assert!((weighted[1] - uniform[1] * (1.0 + USAGE_MAX_GROWTH * 0.5)).abs() < 1e-9);
+        assert_eq!(weighted[2], uniform[2]);
+    }
+
+    #[test]
+    fn test_slot_scaling() {
+        let radius = |filled: &[usize], scaling| {
+            SlotGeometry::calculate(filled[0], filled, Point::default(), 1.0, scaling).radius
+        };
+        let default = SlotScaling::default();
+
+        // a full ring leaves no room to grow, whatever the curve
+        let full: Vec<usize> = (0..SLOT_COUNT).collect();
+        assert_eq!(radius(&full, default), SLOT_RADIUS);
+        // two opposite slots each have four steps of space: sqrt(4) = 2
+        assert_eq!(radius(&[0, 4], default), SLOT_RADIUS * 2.0);
+        // a lone slot would get sqrt(8), but is capped
+        assert_eq!(radius(&[0], default), SLOT_RADIUS * SLOT_SCALE_MAX);
+
+        let flat = SlotScaling {
+            max: 2.5,
+            curve: 0.0,
+        };
+        assert_eq!(radius(&[0], flat), SLOT_RADIUS);
+        let linear = SlotScaling {
+            max: 10.0,
+            curve: 1.0,
+        };
+        assert_eq!(radius(&[0, 4], linear), SLOT_RADIUS * 4.0);
+    }
+
+    #[test]
+    fn test_classless_windows() {
+        let titled = |address: &str, class: &str, title: &str| ActiveClient {
+            title: ClientTitle::new(title),
+            ..client(address, class)
+        };
+        let clients = vec![
+            titled("0x1", "", "Picture-in-Picture"),
+            titled("0x2", "halo-test-kitty", "~"),
+        ];
+        // an open slot has no class either, but doesn't cover classless windows
+        let mut state = state_with(&[(0, "firefox")], Point::default());
+        state.slots[1] = Slot {
+            open: Some("https://example.com".into()),
+            ..slot("Example", "", "")
+        };
+        state.show_subslots = true;
+
+        state.update_clients(clients.clone());
+        let labels: Vec<_> = state.subslots.iter().map(SubSlot::label).collect();
+        assert_eq!(labels, ["Picture-in-Picture", "halo-test-kitty"]);
+        assert!(state.subslots[0].pixbuf.is_none());
+
+        state.skip_classless_windows = true;
+        state.update_clients(clients);
+        assert_eq!(state.subslots.len(), 1);
+        assert_eq!(state.subslots[0].label(), "halo-test-kitty");
+    }
+
+    #[test]
+    fn test_urgent_windows_pulse() {
+        let flagged = |address: &str, class: &str, urgent: bool| ActiveClient {
+            urgent,
+            ..client(address, class)
+        };
+        let mut state = state_with(&[(0, "firefox"), (2, "kitty")], Point::default());
+        state.show_subslots = true;
+
+        state.update_clients(vec![flagged("0x1", "firefox", false)]);
+        assert!(!state.is_pulsing());
+
+        state.update_clients(vec![
+            flagged("0x1", "firefox", true),
+            flagged("0x2", "halo-test-discord", false),
+        ]);
+        assert!(state.slots[0].is_urgent(&state.urgent_classes));
+        assert!(!state.slots[2].is_urgent(&state.urgent_classes));
+        assert!(state.is_pulsing());
+
+        // an unslotted window pulses its subslot
+        state.update_clients(vec![flagged("0x2", "halo-test-discord", true)]);
+        assert!(!state.slots[0].is_urgent(&state.urgent_classes));
+        assert!(state.is_pulsing());
+
+        state.animations_enabled = false;
+        assert!(!state.is_pulsing());
+    }
+
+    #[test]
+    fn test_update_slots_keeps_unchanged() {
+        let slot_config = |direction, app: &str| SlotConfig {
+            direction: Some(direction),
+            app: Some(AppQuery::new(app)),
+            ..Default::default()
+        };
+        let mut configs = vec![
+            slot_config(Direction::North, "halo-test-a"),
+            slot_config(Direction::East, "halo-test-b"),
+        ];
+        let mut state = State::new(
+            build_ring(&configs),
+            Point::default(),
+            Vec::new(),
+            1.0,
+            false,
+        );
+        assert_eq!(state.update_slots(&configs), 0);
+
+        // marks the slot, which a rebuild would reset
+        state.slots[0].loading = true;
+        configs[1].toggle = true;
+        configs.push(slot_config(Direction::West, "halo-test-c"));
+        assert_eq!(state.update_slots(&configs), 2);
+        assert!(state.slots[0].loading);
+        assert!(state.slots[2].toggle);
+        assert!(state.slots[6].app.is_some());
+
+        configs.remove(0);
+        assert_eq!(state.update_slots(&configs), 1);
+        assert!(state.slots[0].app.is_none());
+
+        // disabling is the same as removing, and enabling again brings the slot back
+        configs[1].enabled = false;
+        assert_eq!(state.update_slots(&configs), 1);
+        assert!(state.slots[6].app.is_none());
+        assert!(build_ring(&configs)[6].app.is_none());
+        configs[1].enabled = true;
+        assert_eq!(state.update_slots(&configs), 1);
+        assert!(state.slots[6].app.is_some());
+    }
+
+    #[test]
+    fn test_subslots_on_active_monitor() {
+        let on_monitor = |address: &str, class: &str, monitor: Option<MonitorId>| ActiveClient {
+            monitor,
+            ..client(address, class)
+        };
+        let clients = vec![
+            on_monitor("0x1", "halo-test-kitty", Some(0)),
+            on_monitor("0x2", "halo-test-mpv", Some(1)),
+            on_monitor("0x3", "halo-test-foot", None),
+        ];
+        let mut state = state_with(&[(0, "firefox")], Point::default());
+        state.show_subslots = true;
+        let classes = |state: &State| -> Vec<String> {
+            state
+                .subslots
+                .iter()
+                .map(|s| s.client.class.to_string())
+                .collect()
+        };
+
+        state.update_clients(clients.clone());
+        assert_eq!(classes(&state).len(), 3);
+
+        state.active_monitor = Some(1);
+        state.update_clients(clients.clone());
+        assert_eq!(classes(&state), ["halo-test-mpv"]);
+
+        // the switch ring still has every window
+        state.switch_mode = true;
+        state.update_clients(clients);
+        assert_eq!(classes(&state).len(), 3);
+    }
+
+    #[test]
+    fn test_update_clients_keeps_hovered_subslot() {
+        let on_workspace = |address: &str, class: &str, workspace: &str| ActiveClient {
+            workspace_name: workspace.to_string(),
+            ..client(address, class)
+        };
+        let mut state = state_with(&[(0, "firefox")], Point::default());
+        state.show_subslots = true;
+        state.ignore_special_workspaces = true;
+
+        state.update_clients(vec![
+            on_workspace("0x1", "firefox", "1"),
+            on_workspace("0x2", "halo-test-kitty", "1"),
+            on_workspace("0x3", "halo-test-foot", "special:scratch"),
+        ]);
+        // the slot covers firefox, and special workspaces don't count as running
+        assert_eq!(state.subslots.len(), 2);
+        assert_eq!(
+            state.active_classes,
+            vec![
+                WindowClass::new("firefox"),
+                WindowClass::new("halo-test-kitty")
+            ]
+        );
+
+        state.subslot_hover = Some(1);
+        state.update_clients(vec![on_workspace(
+            "0x3",
+            "halo-test-foot",
+            "special:scratch",
+        )]);
+        assert_eq!(state.subslots.len(), 1);
+        assert_eq!(state.subslot_hover, Some(0));
+        assert_eq!(state.subslots[0].key, SUB_KEYS[0]);
+    }
+
+    #[test]
+    fn test_name_override() {
+        let found = slot("Terminal", "halo-test-terminal", "halo-test-terminal").app;
+        let slot = Slot::for_app(
+            found,
+            &SlotConfig {
+                direction: Some(Direction::North),
+                app: Some(AppQuery::new("terminal")),
+                name: Some("Term".into()),
+                ..Default::default()
+            },
+        );
+        let app = slot.app.as_ref().unwrap();
+        assert_eq!(app.name.as_str(), "Term");
+        // matching still goes by the entry the query found
+        assert_eq!(app.class.as_str(), "halo-test-terminal");
+    }
+
+    #[test]
+    fn test_open_slot() {
+        let slot = Slot::from_config(&SlotConfig {
+            direction: Some(Direction::North),
+            open: Some("https://example.com".into()),
+            ..Default::default()
+        });
+        assert_eq!(slot.open.as_deref(), Some("https://example.com"));
+        assert_eq!(
+            slot.app.as_ref().unwrap().name.as_str(),
+            "https://example.com"
+        );
+        assert!(!slot.is_broken());
+        assert!(!slot.is_running(&[WindowClass::new("")]));
+
+        assert_eq!(open_icon_name("HTTPS://example.com"), "web-browser");
+        assert_eq!(open_icon_name("/"), "folder");
+        assert_eq!(open_icon_name("~/notes/todo.md"), "text-x-generic");
+    }
+
+    #[test]
+    fn test_slot_running_and_broken() {
+        let term = slot("Terminal", "com.mitchellh.ghostty", "ghostty");
+        let active = vec![WindowClass::new("COM.MITCHELLH.GHOSTTY")];
+        assert!(term.is_running(&active));
+        assert!(!term.is_running(&[WindowClass::new("firefox")]));
+        assert!(!term.is_broken());
+
+        assert!(slot("Missing", "missing", "").is_broken());
+        assert!(!Slot::empty().is_broken());
+    }
+
+    #[test]
+    fn test_allocate_subslots_breaks_ties_by_angle() {
+        let segments = vec![
+            AngularSegment::new(-PI, -PI / 3.0),
+            AngularSegment::new(-PI / 3.0, PI / 3.0),
+            AngularSegment::new(PI / 3.0, PI),
+        ];
+
+        let first = allocate_subslots(&segments, 2);
+        assert_eq!(first, vec![1, 1, 0]);
+        for _ in 0..10 {
+            assert_eq!(allocate_subslots(&segments, 2), first);
+        }
+
+        // input order must not matter, only the segments' angles
+        let reversed: Vec<_> = segments.iter().rev().copied().collect();
+        assert_eq!(allocate_subslots(&reversed, 2), vec![0, 1, 1]);
+    }
+
+    #[test]
+    fn test_subslots_never_share_a_center() {
+        let numbered = |i: usize| client(&format!("0x{i}"), &format!("halo-test-{i}"));
+        let names = ["n", "ne", "e", "se", "s", "sw", "w", "nw"];
+        let layouts: [&[usize]; 4] = [&[], &[0], &[0, 1, 3, 4, 5, 7], &[0, 1, 2, 3, 4, 5, 6, 7]];
+
+        for layout in layouts {
+            let filled: Vec<_> = layout.iter().map(|&i| (i, names[i])).collect();
+            for count in [1, 2, 5, SUB_KEYS.len()] {
+                let mut state = state_with(&filled, Point::default());
+                state.show_subslots = true;
+                state.update_clients((0..count).map(numbered).collect());
+                assert_eq!(state.subslots.len(), count);
+
+                let centers: Vec<_> = state.subslots.iter().map(|s| s.geometry.center).collect();
+                for (i, a) in centers.iter().enumerate() {
+                    for b in &centers[i + 1..] {
+                        assert!(
+                            (a.x - b.x).hypot(a.y - b.y) > 1e-6,
+                            "{count} subslots around {layout:?} overlap at {a:?}"
+                        );
+                    }
+                }
+            }
         }
+
+        // a degenerate segment still hands out every subslot
+        assert_eq!(allocate_subslots(&[AngularSegment::new(1.0, 1.0)], 3), [3]);
+        let tiny = [
+            AngularSegment::new(0.0, 1e-9),
+            AngularSegment::new(1.0, 1.0),
+        ];
+        assert_eq!(allocate_subslots(&tiny, 4).iter().sum::<usize>(), 4);
+    }
+
+    #[test]
+    fn test_icon_tint_skips_full_color_icons() {
+        // 2x2 RGBA with a padded row: a dark gray glyph, an antialiased edge, a transparent pixel
+        let gray = [40, 40, 44, 255, 200, 200, 200, 120, 0, 0, 0, 0];
+        let mut pixels = [gray[..8].to_vec(), vec![9, 9], gray[4..].to_vec()].concat();
+        assert!(pixels_monochrome(&pixels, 2, 2, 10, 4));
+        pixels[0] = 220;
+        assert!(!pixels_monochrome(&pixels, 2, 2, 10, 4));
+        assert!(!pixels_monochrome(&[0; 16], 2, 2, 8, 4));
+
+        let white = "white".parse().unwrap();
+        let mut firefox = slot("Firefox", "firefox", "firefox");
+        assert_eq!(firefox.icon_tint(Some(IconTint::Color(white))), None);
+        firefox.config = Some(SlotConfig {
+            icon_tint: Some(IconTint::Full {
+                color: "red".parse().unwrap(),
+                force: true,
+            }),
+            ..Default::default()
+        });
+        let forced = firefox.icon_tint(Some(IconTint::Color(white))).unwrap();
+        assert_eq!((forced.red, forced.green), (1.0, 0.0));
+
+        firefox.config = None;
+        firefox.monochrome = true;
+        assert_eq!(
+            firefox.icon_tint(Some(IconTint::Color(white))),
+            Some(white.0)
+        );
+        assert_eq!(firefox.icon_tint(None), None);
+    }
+
+    #[test]
+    fn test_category_ring_replaces_slots() {
+        let center = Point::new(500.0, 500.0);
+        let mut state = state_with(&[(0, "north"), (2, "east")], center);
+        state.style = MenuStyle::Pie;
+        let apps: Vec<_> = ["code", "kate", "zed", "helix", "gitg"]
+            .iter()
+            .filter_map(|name| slot(name, name, name).app)
+            .collect();
+
+        state.show_category_ring(apps);
+        assert_eq!(state.slots.len(), 5);
+        assert_eq!(state.drawn_style(), MenuStyle::Circles);
+        // spread evenly from North, whatever the direction indices would say
+        let third = state.slot_geometries[2].clone().unwrap();
+        assert!(third.center.y > center.y && third.center.x > center.x);
+        state.update_cursor(Point::new(560.0, 580.0));
+        assert_eq!(state.hover_index, Some(2));
+        assert!(state.move_keyboard_focus(false));
+        assert_eq!(state.keyboard_index, Some(1));
+
+        // a reload puts the configured slots back
+        state.update_slots(&[]);
+        assert!(!state.in_category_ring());
+        assert_eq!(state.slots.len(), SLOT_COUNT);
+
+        // no more apps than there are subslot keys
+        state.sub_keys.truncate(3);
+        let apps: Vec<_> = ["code", "kate", "zed", "helix"]
+            .iter()
+            .filter_map(|name| slot(name, name, name).app)
+            .collect();
+        state.show_category_ring(apps);
+        assert_eq!(state.slots.len(), 3);
+    }
+
+    #[test]
+    fn test_running_only_hides_idle_slots() {
+        let center = Point::new(500.0, 500.0);
+        let mut state = state_with(&[(0, "north"), (2, "east"), (4, "south")], center);
+        state.active_classes = vec![WindowClass::new("East")];
+
+        state.toggle_running_only();
+        assert_eq!(state.filled_slot_indices(), vec![2]);
+        assert!(state.slot_geometries[0].is_none());
+        // pointing straight at an idle slot still lands on the running one
+        state.update_cursor(Point::new(500.0, 400.0));
+        assert_eq!(state.hover_index, Some(2));
+        assert_eq!(state.select_index(4), None);
+        // the config is untouched, so toggling brings the slots back
+        state.toggle_running_only();
+        assert_eq!(state.filled_slot_indices(), vec![0, 2, 4]);
+    }
+
+    #[test]
+    fn test_subslots_group_by_workspace() {
+        let on_workspace = |address: &str, workspace_id: i32| ActiveClient {
+            workspace_id,
+            workspace_name: workspace_id.to_string(),
+            ..client(address, &format!("halo-test-{address}"))
+        };
+        let center = Point::new(500.0, 500.0);
+        let mut state = state_with(&[(0, "north")], center);
+        state.show_subslots = true;
+        state.group_subslots_by_workspace = true;
+
+        state.update_clients(vec![
+            on_workspace("a", 2),
+            on_workspace("b", 1),
+            on_workspace("c", 2),
+        ]);
+        let order: Vec<_> = state
+            .subslots
+            .iter()
+            .map(|s| s.client.workspace_id)
+            .collect();
+        assert_eq!(order, [1, 2, 2]);
+        assert_eq!(state.subslots[0].key, SUB_KEYS[0]);
+
+        let labels = state.workspace_labels();
+        assert_eq!(
+            labels.iter().map(|(_, name)| *name).collect::<Vec<_>>(),
+            ["1", "2"]
+        );
+        // each label sits outside its own subslots
+        let (at, _) = labels[1];
+        let subslot = &state.subslots[1].geometry;
+        let reach = |p: Point| (p.x - center.x).hypot(p.y - center.y);
+        assert!(reach(at) > reach(subslot.center) + subslot.radius);
+
+        state.group_subslots_by_workspace = false;
+        assert!(state.workspace_labels().is_empty());
+    }
+
+    #[test]
+    fn test_subslots_take_the_pointer_first() {
+        let window = |address: &str| client(address, &format!("halo-test-{address}"));
+        let center = Point::new(500.0, 500.0);
+        let mut state = state_with(&[(0, "north")], center);
+        state.show_subslots = true;
+        state.update_clients(vec![window("a"), window("b")]);
+
+        let on_subslot = state.subslots[1].geometry.center;
+        assert_eq!(state.subslot_at(on_subslot), Some(1));
+        let action = state.update_cursor(on_subslot);
+        assert!(action.should_redraw);
+        assert!(!action.should_activate);
+        assert_eq!(state.subslot_hover, Some(1));
+        assert_eq!(state.hover_index, None);
+
+        // off the subslot, the slot in that direction is hovered again
+        let action = state.update_cursor(Point::new(500.0, 300.0));
+        assert_eq!(state.subslot_hover, None);
+        assert_eq!(state.hover_index, Some(0));
+        assert!(action.should_activate);
+        assert_eq!(state.subslot_at(center), None);
+    }
+
+    #[test]
+    fn test_slot_windows_cycle() {
+        let mut state = state_with(&[(0, "kitty"), (2, "zen")], Point::new(500.0, 500.0));
+        state.set_slot_windows(&[
+            client("0x1", "Kitty"),
+            client("0x2", "zen"),
+            client("0x3", "kitty"),
+        ]);
+
+        let kitty = &state.slots[0];
+        assert_eq!(kitty.windows, [Address::new("0x1"), Address::new("0x3")]);
+        assert_eq!(state.slots[2].windows, [Address::new("0x2")]);
+        assert!(state.slots[1].windows.is_empty());
+
+        let at = |address: &str| Some(Address::new(address));
+        assert_eq!(kitty.window_after(None).cloned(), at("0x1"));
+        assert_eq!(kitty.window_after(at("0x1").as_ref()).cloned(), at("0x3"));
+        assert_eq!(kitty.window_after(at("0x3").as_ref()).cloned(), at("0x1"));
+        // focus was on another app's window
+        assert_eq!(kitty.window_after(at("0x2").as_ref()).cloned(), at("0x1"));
+        assert_eq!(state.slots[1].window_after(None), None);
+    }
+
+    #[test]
+    fn test_rescale_shrinks_hit_tests_too() {
+        let center = Point::new(500.0, 500.0);
+        let mut state = state_with(&[(0, "north")], center);
+        let full = state.slot_geometries[0].as_ref().unwrap().center;
+
+        state.rescale(0.5);
+        assert_eq!(state.scale_factor, 0.5);
+        let compact = state.slot_geometries[0].as_ref().unwrap().center;
+        assert!((center.y - compact.y - (center.y - full.y) / 2.0).abs() < 1e-9);
+
+        // short of the full-size activation distance, past the compact one
+        let action = state.update_cursor(Point::new(500.0, 420.0));
+        assert_eq!(state.hover_index, Some(0));
+        assert!(action.should_activate);
+
+        let full_diameter = 2.0 * state_with(&[(0, "north")], center).ring_radius();
+        state.min_diameter = Some(full_diameter / 2.0);
+        state.rescale(0.5);
+        assert!((2.0 * state.ring_radius() - full_diameter / 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_off_monitor_anchor_is_pulled_back() {
+        let mut state = state_with(&[(0, "north")], Point::default());
+        state.refresh(Point::new(5000.0, -40.0), Vec::new(), 1440.0);
+        state.rescale(0.7);
+        assert!(state.clamp_center(2560.0, 1440.0));
+        assert_eq!(state.center, Point::new(2560.0, 0.0));
+        assert!(state.in_dead_zone(Point::new(2560.0, 0.0)));
+        let north = state.slot_geometries[0].as_ref().unwrap();
+        assert_eq!(north.center.x, 2560.0);
+    }
+
+    #[test]
+    fn test_clamp_center_keeps_ring_on_surface() {
+        let mut state = state_with(&[(0, "north")], Point::new(300.0, 200.0));
+        assert!(!state.clamp_center(1920.0, 1080.0));
+        assert!(!state.clamp_center(0.0, 0.0));
+
+        // measured against the monitor, drawn into a surface that isn't that size yet
+        let mut state = state_with(&[(0, "north")], Point::new(1800.0, 1000.0));
+        assert!(state.clamp_center(1280.0, 720.0));
+        assert_eq!(state.center, Point::new(1280.0, 720.0));
+        let north = state.slot_geometries[0].as_ref().unwrap().center;
+        assert_eq!(north.x, 1280.0);
+        // hit tests go by the moved center too
+        assert!(state.in_dead_zone(Point::new(1280.0, 720.0)));
+    }
+
+    #[test]
+    fn test_empty_config_explains_the_blank_ring() {
+        // only a missing or broken file gets the setup slot; `slots = []` is taken as meant
+        let slots = State::init_slots(&Config::default());
+        assert_eq!(slots.len(), SLOT_COUNT);
+        assert!(slots.iter().all(|s| s.app.is_none() && s.config.is_none()));
+
+        let mut state = State::new(slots, Point::new(500.0, 500.0), Vec::new(), 1.0, false);
+        state.refresh(Point::new(500.0, 500.0), Vec::new(), REFERENCE_HEIGHT);
+        assert!(state.slot_geometries.iter().all(Option::is_none));
+        assert!(state.empty_hint().unwrap().contains("config.toml"));
+    }
+
+    #[test]
+    fn test_empty_hint() {
+        let center = Point::new(500.0, 500.0);
+        assert_eq!(state_with(&[(0, "north")], center).empty_hint(), None);
+
+        let mut state = state_with(&[], center);
+        assert!(state.empty_hint().unwrap().contains("no slots"));
+        state.running_only = true;
+        assert!(state.empty_hint().unwrap().contains("running"));
+    }
+
+    #[test]
+    fn test_slot_halves() {
+        let center = Point::new(500.0, 500.0);
+        let mut state = state_with(&[(0, "north")], center);
+        // built from an offline entry, so no lookup runs
+        state.slots[0] = Slot::for_app(
+            slot("halves", "halo-test-halves", "halo-test-halves").app,
+            &SlotConfig {
+                direction: Some(Direction::North),
+                app: Some(AppQuery::new("halo-test-halves")),
+                right_exec: Some(ExecCommand::new("{query} --new-window").into()),
+                ..Default::default()
+            },
+        );
+        assert_eq!(state.select_at(Point::new(500.0, 420.0)), Some(0));
+
+        let slot_center = state.slot_geometries[0].as_ref().unwrap().center;
+        let left = Point::new(slot_center.x - 10.0, slot_center.y);
+        let right = Point::new(slot_center.x + 10.0, slot_center.y);
+        assert_eq!(state.hovered_half(left), Some(SlotHalf::Left));
+        assert_eq!(state.hovered_half(right), Some(SlotHalf::Right));
+        assert_eq!(state.hovered_half(Point::new(500.0, 900.0)), None);
+
+        let slot = &state.slots[0];
+        assert_eq!(slot.half_exec(SlotHalf::Left), None);
+        assert_eq!(
+            slot.half_exec(SlotHalf::Right).map(ToString::to_string),
+            Some("halo-test-halves --new-window".to_string())
+        );
+    }
+
+    #[test]
+    fn test_icons_are_preloaded() {
+        let icon = PathBuf::from("/halo-test/icon.png");
+        let mut waiting = slot("north", "north", "north");
+        waiting.app.as_mut().unwrap().icon = icon.clone();
+        waiting.loading = true;
+        let mut group = slot("group", "", "");
+        group.children = vec![waiting.clone()];
+        let mut state = state_with(&[], Point::default());
+        state.slots[0] = waiting;
+        state.slots[2] = group;
+        assert!(state.is_loading());
+        assert_eq!(state.pending_icons(), vec![icon.clone()]);
+
+        let decoded = IconData::from_pixbuf(&load_setup_icon().unwrap());
+        state.set_icons(vec![(icon, Some(decoded))]);
+        assert!(!state.is_loading());
+        assert!(state.pending_icons().is_empty());
+        assert!(state.slots[0].pixbuf.is_some());
+        assert!(state.slots[2].children[0].pixbuf.is_some());
+    }
+
+    #[test]
+    fn test_setup_slot_has_icon() {
+        let mut slot = Slot::from_config(&SlotConfig {
+            direction: Some(Direction::North),
+            app: Some(AppQuery::new("Setup")),
+            exec: Some(ExecCommand::new(SETUP_EXEC).into()),
+            ..Default::default()
+        });
+        assert!(!slot.is_broken());
+        assert!(slot.pixbuf.is_some());
+        assert!(slot.monochrome);
+
+        // nothing for a theme change to re-resolve, so the bundled icon stays
+        slot.reload_icon();
+        assert!(slot.pixbuf.is_some());
     }
 }
@@ -1,13 +1,34 @@
 use super::model::{Slot, SlotGeometry, State, SubSlot};
-use super::{CENTER_CIRCLE_RADIUS, ICON_INACTIVE_ALPHA, ICON_SIZE};
+use super::{
+    ANGLE_STEP, CENTER_CIRCLE_RADIUS, CLOSE_SHRINK, FLASH_GROWTH, ICON_INACTIVE_ALPHA,
+    ICON_SHADOW_ALPHA, ICON_SHADOW_OFFSET, ICON_SHADOW_SOFTNESS, INNER_RADIUS, MENU_RADIUS,
+    PIE_GAP, PIE_OUTER_RADIUS, WORKSPACE_LABEL_SIZE,
+};
+use crate::config::{CenterContent, ColorValue, DimMode, MenuStyle, SlotColors};
 use crate::gui::theme::ThemeColors;
 use cairo::Context;
 use gdk_pixbuf::Pixbuf;
 use gdk4::prelude::*;
-use hypraise::wm::WindowClass;
 use palette::Srgba;
+use std::borrow::Cow;
 use std::f64::consts::PI;
 use std::iter::zip;
+use unicode_segmentation::UnicodeSegmentation;
+
+const SPINNER_PERIOD_US: f64 = 1_000_000.0;
+const URGENT_PULSE_PERIOD_US: f64 = 1_600_000.0;
+const FOCUS_RING_WIDTH: f64 = 3.0;
+
+/// The urgent color, breathing between faint and full while `animated`.
+fn urgent_color(colors: &ThemeColors, animated: bool) -> Srgba<f64> {
+    if !animated {
+        return colors.urgent;
+    }
+    let phase = glib::monotonic_time() as f64 / URGENT_PULSE_PERIOD_US * 2.0 * PI;
+    let mut color = colors.urgent;
+    color.alpha *= 0.65 + 0.35 * phase.sin();
+    color
+}
 
 fn draw_slot_circle(
     cr: &Context,
@@ -21,19 +42,164 @@ fn draw_slot_circle(
     cr.fill()
 }
 
+/// Fills the pie sector centered on `angle`, between the `inner` and `outer` radii, leaving
+/// `gap` pixels between it and its neighbours.
+fn draw_slot_sector(
+    cr: &Context,
+    center: hypraise::wm::Point,
+    radii: (f64, f64),
+    angle: f64,
+    gap: f64,
+    color: Srgba<f64>,
+) -> Result<(), cairo::Error> {
+    set_source(cr, color);
+    sector_path(cr, center, radii, angle, gap);
+    cr.fill()
+}
+
+fn sector_path(
+    cr: &Context,
+    center: hypraise::wm::Point,
+    (inner, outer): (f64, f64),
+    angle: f64,
+    gap: f64,
+) {
+    // the same width of gap along both arcs, so the edges stay parallel
+    let half = ANGLE_STEP / 2.0;
+    let (outer_half, inner_half) = (half - gap / 2.0 / outer, half - gap / 2.0 / inner);
+    cr.new_path();
+    cr.arc(
+        center.x,
+        center.y,
+        outer,
+        angle - outer_half,
+        angle + outer_half,
+    );
+    cr.line_to(
+        center.x + inner * (angle + inner_half).cos(),
+        center.y + inner * (angle + inner_half).sin(),
+    );
+    cr.arc_negative(
+        center.x,
+        center.y,
+        inner,
+        angle + inner_half,
+        angle - inner_half,
+    );
+    cr.close_path();
+}
+
+/// Scales an icon so its larger side spans 75% of the slot diameter, keeping non-square
+/// icons (banners, tall logos) inside the circle. Returns the scale and the drawn size.
+fn fit_icon(width: i32, height: i32, slot_radius: f64) -> (f64, f64, f64) {
+    let largest = width.max(height).max(1) as f64;
+    let scale = (slot_radius * 2.0 * 0.75) / largest;
+    (scale, width as f64 * scale, height as f64 * scale)
+}
+
+/// Shortens `text` to the longest prefix that fits `max_width` with an ellipsis appended,
+/// cutting only at grapheme boundaries. `measure` gives the drawn width of a string.
+fn truncate_to_fit(text: &str, max_width: f64, measure: impl Fn(&str) -> f64) -> Cow<'_, str> {
+    if measure(text) <= max_width {
+        return Cow::Borrowed(text);
+    }
+    let ends: Vec<usize> = text
+        .grapheme_indices(true)
+        .map(|(i, g)| i + g.len())
+        .collect();
+    let with_ellipsis = |n: usize| format!("{}…", &text[..n.checked_sub(1).map_or(0, |i| ends[i])]);
+
+    // widths grow with the prefix, so search for the longest one that fits
+    let (mut lo, mut hi) = (0, ends.len());
+    while lo < hi {
+        let mid = (lo + hi).div_ceil(2);
+        if measure(&with_ellipsis(mid)) <= max_width {
+            lo = mid;
+        } else {
+            hi = mid - 1;
+        }
+    }
+    Cow::Owned(with_ellipsis(lo))
+}
+
+/// Breaks `text` at spaces into lines that fit `max_width`, at most `max_lines` of them. What
+/// doesn't fit goes on the last line, which is truncated, as is a word too wide for any line.
+fn wrap_to_fit(
+    text: &str,
+    max_width: f64,
+    max_lines: usize,
+    measure: impl Fn(&str) -> f64,
+) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let candidate = if line.is_empty() {
+            word.to_string()
+        } else {
+            format!("{line} {word}")
+        };
+        if line.is_empty() || measure(&candidate) <= max_width {
+            line = candidate;
+        } else {
+            lines.push(std::mem::replace(&mut line, word.to_string()));
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    if lines.len() > max_lines.max(1) {
+        let rest = lines.split_off(max_lines.max(1) - 1).join(" ");
+        lines.push(rest);
+    }
+    lines
+        .iter()
+        .map(|line| truncate_to_fit(line, max_width, &measure).into_owned())
+        .collect()
+}
+
+/// Draws `text` wrapped by [`wrap_to_fit`] with the context's current font, as a stack of
+/// lines centered on `center`.
+fn draw_wrapped_text(
+    cr: &Context,
+    text: &str,
+    center: hypraise::wm::Point,
+    max_width: f64,
+    max_lines: usize,
+) -> Result<(), cairo::Error> {
+    let lines = wrap_to_fit(text, max_width, max_lines, |s| {
+        cr.text_extents(s).map_or(0.0, |ext| ext.x_advance())
+    });
+    let line_height = cr.font_extents()?.height();
+    let first = -(lines.len().saturating_sub(1) as f64) * line_height / 2.0;
+    for (i, line) in lines.iter().enumerate() {
+        if let Ok(ext) = cr.text_extents(line) {
+            cr.move_to(
+                center.x - ext.width() / 2.0,
+                center.y + first + i as f64 * line_height + ext.height() / 2.0,
+            );
+            cr.show_text(line)?;
+        }
+    }
+    Ok(())
+}
+
+/// [`truncate_to_fit`] measured with the context's current font.
+fn fit_text<'t>(cr: &Context, text: &'t str, max_width: f64) -> Cow<'t, str> {
+    truncate_to_fit(text, max_width, |s| {
+        cr.text_extents(s).map_or(0.0, |ext| ext.x_advance())
+    })
+}
+
 fn draw_slot_icon(
     cr: &Context,
     pixbuf: &Pixbuf,
     center: hypraise::wm::Point,
     slot_radius: f64,
     dimmed: bool,
+    shadow: bool,
+    tint: Option<Srgba<f64>>,
 ) -> Result<(), cairo::Error> {
-    // fit icon into slot
-    let icon_scale = (slot_radius * 2.0 * 0.75) / ICON_SIZE as f64;
-    let (iw, ih) = (
-        pixbuf.width() as f64 * icon_scale,
-        pixbuf.height() as f64 * icon_scale,
-    );
+    let (icon_scale, iw, ih) = fit_icon(pixbuf.width(), pixbuf.height(), slot_radius);
     // center icon in slot
     let (ix, iy) = (center.x - iw / 2.0, center.y - ih / 2.0);
 
@@ -41,109 +207,375 @@ fn draw_slot_icon(
     cr.translate(ix, iy);
     cr.scale(icon_scale, icon_scale);
 
+    if shadow {
+        let alpha = if dimmed {
+            ICON_SHADOW_ALPHA * ICON_INACTIVE_ALPHA
+        } else {
+            ICON_SHADOW_ALPHA
+        };
+        draw_icon_shadow(cr, pixbuf, slot_radius / icon_scale, alpha)?;
+    }
+
     // dim icon if app not running and not hovered
     if dimmed {
         cr.push_group();
-        cr.set_source_pixbuf(pixbuf, 0.0, 0.0);
-        cr.paint()?;
+        paint_icon(cr, pixbuf, tint)?;
         cr.pop_group_to_source()?;
         cr.paint_with_alpha(ICON_INACTIVE_ALPHA)?;
     } else {
-        cr.set_source_pixbuf(pixbuf, 0.0, 0.0);
-        cr.paint()?;
+        paint_icon(cr, pixbuf, tint)?;
     }
     cr.restore()
 }
 
+/// Paints the icon as is, or with `tint` filling its alpha mask.
+fn paint_icon(cr: &Context, pixbuf: &Pixbuf, tint: Option<Srgba<f64>>) -> Result<(), cairo::Error> {
+    cr.set_source_pixbuf(pixbuf, 0.0, 0.0);
+    let Some(tint) = tint else {
+        return cr.paint();
+    };
+    let icon = cr.source();
+    set_source(cr, tint);
+    cr.mask(&icon)
+}
+
+/// Paints the icon's silhouette in black, down and to the right. Cairo can't blur, so a few
+/// faint, slightly spread passes soften the edge instead. `slot_radius` is in icon pixels.
+fn draw_icon_shadow(
+    cr: &Context,
+    pixbuf: &Pixbuf,
+    slot_radius: f64,
+    alpha: f64,
+) -> Result<(), cairo::Error> {
+    cr.push_group();
+    cr.set_source_pixbuf(pixbuf, 0.0, 0.0);
+    cr.paint()?;
+    let silhouette = cr.pop_group()?;
+
+    let offset = slot_radius * ICON_SHADOW_OFFSET;
+    let spread = slot_radius * ICON_SHADOW_SOFTNESS;
+    let passes = [(0.0, 0.0), (1.0, 0.0), (-1.0, 0.0), (0.0, 1.0), (0.0, -1.0)];
+    // where all passes overlap they add up to `alpha`
+    let pass_alpha = 1.0 - (1.0 - alpha).powf(1.0 / passes.len() as f64);
+    cr.set_source_rgba(0.0, 0.0, 0.0, pass_alpha);
+    for (dx, dy) in passes {
+        cr.save()?;
+        cr.translate(offset + dx * spread, offset + dy * spread);
+        cr.mask(&silhouette)?;
+        cr.restore()?;
+    }
+    Ok(())
+}
+
+fn set_source(cr: &Context, color: Srgba<f64>) {
+    let (r, g, b, a) = color.into_components();
+    cr.set_source_rgba(r, g, b, a);
+}
+
+/// Draws translucent text with a drop shadow, centered on `center`.
+fn draw_badge_text(
+    cr: &Context,
+    text: &str,
+    center: hypraise::wm::Point,
+    font_size: f64,
+    colors: &ThemeColors,
+) -> Result<(), cairo::Error> {
+    cr.select_font_face("Sans", cairo::FontSlant::Normal, cairo::FontWeight::Bold);
+    cr.set_font_size(font_size);
+
+    if let Ok(ext) = cr.text_extents(text) {
+        let x = center.x - ext.width() / 2.0 - ext.x_bearing();
+        let y = center.y - ext.height() / 2.0 - ext.y_bearing();
+
+        // shadow
+        cr.move_to(x + 1.0, y + 1.0);
+        set_source(cr, colors.badge_shadow);
+        cr.show_text(text)?;
+
+        cr.move_to(x, y);
+        set_source(cr, colors.badge_text);
+        cr.show_text(text)?;
+    }
+    Ok(())
+}
+
 struct SlotRenderer<'a> {
+    index: usize,
     slot: &'a Slot,
-    geometry: &'a SlotGeometry,
+    /// Where the content goes: the slot's circle, or the centroid of its sector in a pie
+    geometry: SlotGeometry,
     hovered: bool,
-    active_classes: &'a [WindowClass],
+    state: &'a State,
+    hint: Option<&'a str>,
+    /// Activation pulse, 0 at rest up to 1 at its peak
+    pulse: f64,
+    /// Picked with the keyboard, which outlines it
+    focused: bool,
+    /// The center of the ring it's in, which for a parent ring isn't the menu's
+    center: hypraise::wm::Point,
 }
 
 impl<'a> SlotRenderer<'a> {
     fn new(
+        index: usize,
         slot: &'a Slot,
-        geometry: &'a SlotGeometry,
+        geometry: &SlotGeometry,
         hovered: bool,
-        active_classes: &'a [WindowClass],
+        state: &'a State,
+        hint: Option<&'a str>,
+        pulse: f64,
     ) -> Self {
         Self {
+            index,
             slot,
-            geometry,
+            geometry: geometry.clone(),
             hovered,
-            active_classes,
+            state,
+            hint,
+            pulse,
+            focused: false,
+            center: state.center,
+        }
+        .around(state.center)
+    }
+
+    /// Places a pie sector around `center`; circles keep their laid-out geometry.
+    fn around(mut self, center: hypraise::wm::Point) -> Self {
+        self.center = center;
+        if self.state.drawn_style() == MenuStyle::Pie {
+            self.geometry = SlotGeometry::sector(self.index, center, self.state.scale_factor);
         }
+        self
+    }
+
+    /// The slot's radius, swollen while it pulses.
+    fn radius(&self) -> f64 {
+        self.geometry.radius * (1.0 + FLASH_GROWTH * self.pulse)
     }
 
     fn draw(&self, cr: &Context, colors: &ThemeColors) -> Result<(), cairo::Error> {
-        self.draw_circle(cr, colors)?;
+        match self.state.drawn_style() {
+            MenuStyle::Circles => self.draw_circle(cr, colors)?,
+            MenuStyle::Pie => self.draw_sector(cr, colors)?,
+        }
+        if self.focused {
+            self.draw_focus_ring(cr, colors)?;
+        }
         self.draw_content(cr)?;
+        if let Some(hint) = self.hint {
+            self.draw_hint(cr, hint, colors)?;
+        }
+        if self.slot.windows.len() > 1 {
+            self.draw_badge(cr, colors)?;
+        }
         Ok(())
     }
 
+    /// How many windows the slot's app has open, on its top right edge.
+    fn draw_badge(&self, cr: &Context, colors: &ThemeColors) -> Result<(), cairo::Error> {
+        let offset = self.geometry.radius * 0.7;
+        let center = hypraise::wm::Point::new(
+            self.geometry.center.x + offset,
+            self.geometry.center.y - offset,
+        );
+        let count = self.slot.windows.len().to_string();
+        draw_badge_text(cr, &count, center, 12.0 * self.geometry.scale, colors)
+    }
+
+    fn draw_hint(
+        &self,
+        cr: &Context,
+        hint: &str,
+        colors: &ThemeColors,
+    ) -> Result<(), cairo::Error> {
+        // along the bottom edge of the slot, clear of the label
+        let center = hypraise::wm::Point::new(
+            self.geometry.center.x,
+            self.geometry.center.y + self.geometry.radius * 0.85,
+        );
+        draw_badge_text(cr, hint, center, 10.0 * self.geometry.scale, colors)
+    }
+
     fn draw_circle(&self, cr: &Context, colors: &ThemeColors) -> Result<(), cairo::Error> {
-        let state = SlotState::resolve(self.slot, self.hovered, self.active_classes);
+        let state = SlotState::resolve(self.slot, self.hovered, self.state);
         draw_slot_circle(
             cr,
             self.geometry.center,
-            self.geometry.radius,
-            state.color(colors),
-        )
+            self.radius(),
+            state.color(colors, &self.slot.colors, self.state.animations_enabled),
+        )?;
+        if self.pulse > 0.0 {
+            // brighten towards white at the peak of the pulse
+            draw_slot_circle(
+                cr,
+                self.geometry.center,
+                self.radius(),
+                Srgba::new(1.0, 1.0, 1.0, 0.35 * self.pulse),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn draw_sector(&self, cr: &Context, colors: &ThemeColors) -> Result<(), cairo::Error> {
+        let state = SlotState::resolve(self.slot, self.hovered, self.state);
+        let scale = self.state.scale_factor;
+        let radii = (INNER_RADIUS * scale, PIE_OUTER_RADIUS * scale);
+        let angle = SlotGeometry::angle(self.index).0;
+        let gap = PIE_GAP * scale;
+        draw_slot_sector(
+            cr,
+            self.center,
+            radii,
+            angle,
+            gap,
+            state.color(colors, &self.slot.colors, self.state.animations_enabled),
+        )?;
+        if self.pulse > 0.0 {
+            let flash = Srgba::new(1.0, 1.0, 1.0, 0.35 * self.pulse);
+            draw_slot_sector(cr, self.center, radii, angle, gap, flash)?;
+        }
+        Ok(())
+    }
+
+    /// An outline in the hover color, so the keyboard's pick reads apart from the pointer's.
+    fn draw_focus_ring(&self, cr: &Context, colors: &ThemeColors) -> Result<(), cairo::Error> {
+        let scale = self.state.scale_factor;
+        let mut color = colors.hovered;
+        color.alpha = 1.0;
+
+        cr.save()?;
+        set_source(cr, color);
+        cr.set_line_width(FOCUS_RING_WIDTH * scale);
+        match self.state.drawn_style() {
+            MenuStyle::Circles => {
+                let (center, inset) = (self.geometry.center, FOCUS_RING_WIDTH * scale / 2.0);
+                cr.new_path();
+                cr.arc(center.x, center.y, self.radius() - inset, 0.0, 2.0 * PI);
+            }
+            MenuStyle::Pie => sector_path(
+                cr,
+                self.center,
+                (INNER_RADIUS * scale, PIE_OUTER_RADIUS * scale),
+                SlotGeometry::angle(self.index).0,
+                (PIE_GAP + FOCUS_RING_WIDTH) * scale,
+            ),
+        }
+        cr.stroke()?;
+        cr.restore()
     }
 
     fn draw_content(&self, cr: &Context) -> Result<(), cairo::Error> {
         if let Some(pixbuf) = &self.slot.pixbuf {
-            let running = self.slot.is_running(self.active_classes);
+            let running = self.slot.is_running(&self.state.active_classes);
+            let dimmed = !self.hovered
+                && match self.state.dim_mode {
+                    DimMode::Idle => !running,
+                    DimMode::Running => running,
+                };
             draw_slot_icon(
                 cr,
                 pixbuf,
                 self.geometry.center,
-                self.geometry.radius,
-                !running && !self.hovered,
-            )
+                self.radius(),
+                dimmed,
+                self.state.icon_shadow,
+                self.slot.icon_tint(self.state.icon_tint),
+            )?;
+            if self.state.labels_visible
+                && let Some(app) = &self.slot.app
+            {
+                // label sits over the lower part of the icon
+                self.draw_text(cr, &app.name, self.geometry.radius * 0.6, 1)?;
+            }
+            Ok(())
+        } else if self.slot.loading {
+            self.draw_spinner(cr)
         } else if let Some(app) = &self.slot.app {
-            self.draw_text(cr, &app.name)
+            // without an icon there's room to wrap a long name inside the circle
+            self.draw_text(cr, &app.name, 0.0, 3)
         } else {
             Ok(())
         }
     }
 
-    fn draw_text(&self, cr: &Context, text: &str) -> Result<(), cairo::Error> {
+    fn draw_spinner(&self, cr: &Context) -> Result<(), cairo::Error> {
+        // indeterminate: the rotation comes from the clock, so every redraw advances it
+        let turns = glib::monotonic_time() as f64 / SPINNER_PERIOD_US;
+        let start = turns.fract() * 2.0 * PI;
+        let center = self.geometry.center;
+
+        cr.save()?;
+        cr.set_source_rgba(1.0, 1.0, 1.0, ICON_INACTIVE_ALPHA);
+        cr.set_line_width(self.geometry.radius * 0.12);
+        cr.set_line_cap(cairo::LineCap::Round);
+        cr.new_sub_path();
+        cr.arc(
+            center.x,
+            center.y,
+            self.geometry.radius * 0.5,
+            start,
+            start + PI * 1.5,
+        );
+        cr.stroke()?;
+        cr.restore()
+    }
+
+    fn draw_text(
+        &self,
+        cr: &Context,
+        text: &str,
+        y_offset: f64,
+        max_lines: usize,
+    ) -> Result<(), cairo::Error> {
         cr.set_source_rgb(1.0, 1.0, 1.0);
         cr.select_font_face("Sans", cairo::FontSlant::Normal, cairo::FontWeight::Bold);
         cr.set_font_size(12.0 * self.geometry.scale);
-        if let Ok(ext) = cr.text_extents(text) {
-            cr.move_to(
-                self.geometry.center.x - ext.width() / 2.0,
-                self.geometry.center.y + ext.height() / 2.0,
-            );
-            cr.show_text(text)?;
-        }
-        Ok(())
+        let center =
+            hypraise::wm::Point::new(self.geometry.center.x, self.geometry.center.y + y_offset);
+        // a single line may run the full diameter; stacked ones stay clear of the curved edge
+        let max_width = self.geometry.radius * if max_lines > 1 { 1.6 } else { 2.0 };
+        draw_wrapped_text(cr, text, center, max_width, max_lines)
     }
 }
 
 struct SubSlotRenderer<'a> {
     subslot: &'a SubSlot,
+    hovered: bool,
+    shadow: bool,
+    animated: bool,
+    /// Where it's drawn, on its way from the menu's center while fanning out
+    center: hypraise::wm::Point,
 }
 
 impl<'a> SubSlotRenderer<'a> {
-    fn new(subslot: &'a SubSlot) -> Self {
-        Self { subslot }
+    fn new(subslot: &'a SubSlot, hovered: bool, state: &State) -> Self {
+        let from = state.center;
+        let to = subslot.geometry.center;
+        let t = state.fan_out_progress();
+        Self {
+            subslot,
+            hovered,
+            shadow: state.icon_shadow,
+            animated: state.animations_enabled,
+            center: hypraise::wm::Point::new(
+                from.x + (to.x - from.x) * t,
+                from.y + (to.y - from.y) * t,
+            ),
+        }
     }
 
     fn draw(&self, cr: &Context, colors: &ThemeColors) -> Result<(), cairo::Error> {
-        draw_slot_circle(
-            cr,
-            self.subslot.geometry.center,
-            self.subslot.geometry.radius,
-            colors.running,
-        )?;
+        let color = if self.hovered {
+            colors.hovered
+        } else if self.subslot.client.urgent {
+            urgent_color(colors, self.animated)
+        } else {
+            colors.running
+        };
+        draw_slot_circle(cr, self.center, self.subslot.geometry.radius, color)?;
 
         self.draw_content(cr)?;
-        self.draw_badge(cr)?;
+        self.draw_badge(cr, colors)?;
         Ok(())
     }
 
@@ -152,12 +584,14 @@ impl<'a> SubSlotRenderer<'a> {
             draw_slot_icon(
                 cr,
                 pixbuf,
-                self.subslot.geometry.center,
+                self.center,
                 self.subslot.geometry.radius,
                 false,
+                self.shadow,
+                None,
             )
         } else {
-            self.draw_text(cr, &self.subslot.client.class)
+            self.draw_text(cr, self.subslot.label())
         }
     }
 
@@ -165,42 +599,27 @@ impl<'a> SubSlotRenderer<'a> {
         cr.set_source_rgb(1.0, 1.0, 1.0);
         cr.select_font_face("Sans", cairo::FontSlant::Normal, cairo::FontWeight::Bold);
         cr.set_font_size(10.0 * self.subslot.geometry.scale);
-        if let Ok(ext) = cr.text_extents(text) {
+        let text = fit_text(cr, text, self.subslot.geometry.radius * 2.0);
+        if let Ok(ext) = cr.text_extents(&text) {
             cr.move_to(
-                self.subslot.geometry.center.x - ext.width() / 2.0,
-                self.subslot.geometry.center.y + ext.height() / 2.0,
+                self.center.x - ext.width() / 2.0,
+                self.center.y + ext.height() / 2.0,
             );
-            cr.show_text(text)?;
+            cr.show_text(&text)?;
         }
         Ok(())
     }
 
-    fn draw_badge(&self, cr: &Context) -> Result<(), cairo::Error> {
+    fn draw_badge(&self, cr: &Context, colors: &ThemeColors) -> Result<(), cairo::Error> {
         let text = self.subslot.key.to_string().to_uppercase();
-        let center = self.subslot.geometry.center;
-
-        cr.select_font_face("Sans", cairo::FontSlant::Normal, cairo::FontWeight::Bold);
         // size proportional to the slot radius
-        let font_size = self.subslot.geometry.radius * 1.8;
-        cr.set_font_size(font_size);
-
-        if let Ok(ext) = cr.text_extents(&text) {
-            // center text in slot
-            let x = center.x - ext.width() / 2.0 - ext.x_bearing();
-            let y = center.y - ext.height() / 2.0 - ext.y_bearing();
-
-            cr.set_source_rgba(1.0, 1.0, 1.0, 0.6);
-
-            // shadow
-            cr.move_to(x + 1.0, y + 1.0);
-            cr.set_source_rgba(0.0, 0.0, 0.0, 0.5);
-            cr.show_text(&text)?;
-
-            cr.move_to(x, y);
-            cr.set_source_rgba(1.0, 1.0, 1.0, 0.6);
-            cr.show_text(&text)?;
-        }
-        Ok(())
+        draw_badge_text(
+            cr,
+            &text,
+            self.center,
+            self.subslot.geometry.radius * 1.8,
+            colors,
+        )
     }
 }
 
@@ -208,6 +627,7 @@ impl<'a> SubSlotRenderer<'a> {
 enum SlotState {
     Broken,
     Hovered,
+    Urgent,
     Running,
     Idle,
 }
@@ -216,47 +636,116 @@ impl SlotState {
     /// Determines the visual state of a slot based on priority:
     /// 1. Broken (Config error)
     /// 2. Hovered
-    /// 3. Running
-    /// 4. Idle (Default)
-    fn resolve(slot: &Slot, hovered: bool, active_classes: &[WindowClass]) -> Self {
+    /// 3. Urgent (A window wants attention)
+    /// 4. Running
+    /// 5. Idle (Default)
+    fn resolve(slot: &Slot, hovered: bool, state: &State) -> Self {
         if slot.is_broken() {
             Self::Broken
         } else if hovered {
             Self::Hovered
-        } else if slot.is_running(active_classes) {
+        } else if slot.is_urgent(&state.urgent_classes) {
+            Self::Urgent
+        } else if slot.is_running(&state.active_classes) {
             Self::Running
         } else {
             Self::Idle
         }
     }
 
-    fn color(&self, colors: &ThemeColors) -> Srgba<f64> {
+    /// The slot's own color for this state if configured, otherwise the theme's. Urgent slots
+    /// pulse when `animated`.
+    fn color(&self, colors: &ThemeColors, overrides: &SlotColors, animated: bool) -> Srgba<f64> {
+        let pick = |over: Option<ColorValue>, theme: Srgba<f64>| over.map_or(theme, |c| c.0);
         match self {
             Self::Broken => colors.broken,
-            Self::Hovered => colors.hovered,
-            Self::Running => colors.running,
-            Self::Idle => colors.default,
+            Self::Urgent => urgent_color(colors, animated),
+            Self::Hovered => pick(overrides.hovered, colors.hovered),
+            Self::Running => pick(overrides.running, colors.running),
+            Self::Idle => pick(overrides.idle, colors.default),
         }
     }
 }
 
-pub fn draw(cr: &Context, state: &State, colors: &ThemeColors) -> Result<(), cairo::Error> {
+/// Draws the menu into a `width` × `height` area; an empty area draws nothing.
+pub fn draw(
+    cr: &Context,
+    state: &State,
+    colors: &ThemeColors,
+    width: f64,
+    height: f64,
+) -> Result<(), cairo::Error> {
+    if width <= 0.0 || height <= 0.0 {
+        return Ok(());
+    }
+    let closing = state.close_progress();
+    if closing > 0.0 {
+        // shrinks toward the center, which stays put
+        let scale = 1.0 - CLOSE_SHRINK * closing;
+        cr.translate(state.center.x, state.center.y);
+        cr.scale(scale, scale);
+        cr.translate(-state.center.x, -state.center.y);
+    }
+
+    if state.parent_ring_alpha > 0.0 {
+        draw_parent_rings(cr, state, colors)?;
+    }
     draw_center_circle(cr, state, colors)?;
 
     for (i, (slot, geometry)) in zip(&state.slots, &state.slot_geometries).enumerate() {
         if let Some(geometry) = geometry {
-            SlotRenderer::new(
+            let hint = state.keybind_hint(i);
+            let hovered = state.hover_index == Some(i);
+            let mut renderer = SlotRenderer::new(
+                i,
                 slot,
                 geometry,
-                state.hover_index == Some(i),
-                &state.active_classes,
-            )
-            .draw(cr, colors)?;
+                hovered,
+                state,
+                hint,
+                state.flash_amount(i),
+            );
+            renderer.focused = state.keyboard_index == Some(i);
+            renderer.draw(cr, colors)?;
+        }
+    }
+
+    if let Some(hint) = state.empty_hint() {
+        draw_empty_hint(cr, &hint, state)?;
+    }
+
+    for (i, subslot) in state.subslots.iter().enumerate() {
+        SubSlotRenderer::new(subslot, state.subslot_hover == Some(i), state).draw(cr, colors)?;
+    }
+    // labels would trail the subslots while they fan out, so they wait until they're in place
+    if !state.fanning_out() {
+        let font_size = WORKSPACE_LABEL_SIZE * state.scale_factor;
+        for (at, name) in state.workspace_labels() {
+            draw_badge_text(cr, name, at, font_size, colors)?;
         }
     }
+    Ok(())
+}
 
-    for subslot in &state.subslots {
-        SubSlotRenderer::new(subslot).draw(cr, colors)?;
+/// Draws the rings above an open submenu faded behind it, fainter the further up they are,
+/// with the group that was opened shown as hovered.
+fn draw_parent_rings(
+    cr: &Context,
+    state: &State,
+    colors: &ThemeColors,
+) -> Result<(), cairo::Error> {
+    let depth = state.submenu_depth();
+    for (level, ring) in state.parent_rings().enumerate() {
+        cr.push_group();
+        for (i, (slot, geometry)) in zip(ring.slots, ring.geometries).enumerate() {
+            if let Some(geometry) = geometry {
+                SlotRenderer::new(i, slot, geometry, i == ring.opened, state, None, 0.0)
+                    .around(ring.center)
+                    .draw(cr, colors)?;
+            }
+        }
+        cr.pop_group_to_source()?;
+        cr.paint_with_alpha(state.parent_ring_alpha.powi((depth - level) as i32))?;
     }
     Ok(())
 }
@@ -275,5 +764,142 @@ fn draw_center_circle(
         0.0,
         2.0 * PI,
     );
-    cr.fill()
+    cr.fill()?;
+
+    let radius = CENTER_CIRCLE_RADIUS * state.scale_factor;
+    if state.in_submenu() {
+        // the way back out matters more than decoration
+        draw_back_chevron(cr, state.center, radius * 0.4)?;
+        return Ok(());
+    }
+    match &state.center_content {
+        Some(CenterContent::Icon(_)) => match &state.center_icon {
+            Some(pixbuf) => draw_slot_icon(cr, pixbuf, state.center, radius, false, false, None),
+            None => Ok(()),
+        },
+        Some(CenterContent::Text(text)) => draw_center_text(cr, text, state.center, radius),
+        Some(CenterContent::Clock) => {
+            let time = glib::DateTime::now_local().and_then(|now| now.format("%H:%M"));
+            match time {
+                Ok(time) => draw_center_text(cr, &time, state.center, radius),
+                Err(_) => Ok(()),
+            }
+        }
+        Some(CenterContent::Workspace) => match &state.workspace_name {
+            Some(name) => draw_center_text(cr, name, state.center, radius),
+            None => Ok(()),
+        },
+        None => Ok(()),
+    }
+}
+
+fn draw_center_text(
+    cr: &Context,
+    text: &str,
+    center: hypraise::wm::Point,
+    radius: f64,
+) -> Result<(), cairo::Error> {
+    cr.set_source_rgb(1.0, 1.0, 1.0);
+    cr.select_font_face("Sans", cairo::FontSlant::Normal, cairo::FontWeight::Bold);
+    cr.set_font_size(radius * 0.5);
+    let text = fit_text(cr, text, radius * 1.7);
+    if let Ok(ext) = cr.text_extents(&text) {
+        cr.move_to(
+            center.x - ext.width() / 2.0 - ext.x_bearing(),
+            center.y - ext.height() / 2.0 - ext.y_bearing(),
+        );
+        cr.show_text(&text)?;
+    }
+    Ok(())
+}
+
+/// Explains an empty ring, wrapped below the center circle where the slots would be.
+fn draw_empty_hint(cr: &Context, hint: &str, state: &State) -> Result<(), cairo::Error> {
+    cr.set_source_rgba(1.0, 1.0, 1.0, ICON_INACTIVE_ALPHA);
+    cr.select_font_face("Sans", cairo::FontSlant::Normal, cairo::FontWeight::Normal);
+    cr.set_font_size(13.0 * state.scale_factor);
+    let below = hypraise::wm::Point::new(
+        state.center.x,
+        state.center.y + (CENTER_CIRCLE_RADIUS + MENU_RADIUS) * state.scale_factor / 2.0,
+    );
+    draw_wrapped_text(cr, hint, below, MENU_RADIUS * 2.0 * state.scale_factor, 4)
+}
+
+/// Marks the center as the way back out of a submenu.
+fn draw_back_chevron(
+    cr: &Context,
+    center: hypraise::wm::Point,
+    size: f64,
+) -> Result<(), cairo::Error> {
+    cr.save()?;
+    cr.set_source_rgba(1.0, 1.0, 1.0, ICON_INACTIVE_ALPHA);
+    cr.set_line_width(size * 0.3);
+    cr.set_line_cap(cairo::LineCap::Round);
+    cr.set_line_join(cairo::LineJoin::Round);
+    cr.move_to(center.x + size * 0.3, center.y - size * 0.6);
+    cr.line_to(center.x - size * 0.3, center.y);
+    cr.line_to(center.x + size * 0.3, center.y + size * 0.6);
+    cr.stroke()?;
+    cr.restore()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gui::menu::ICON_SIZE;
+
+    #[test]
+    fn test_fit_icon_keeps_wide_icons_inside_slot() {
+        let radius = 52.0;
+
+        let (_, w, h) = fit_icon(ICON_SIZE, ICON_SIZE, radius);
+        assert_eq!((w, h), (78.0, 78.0));
+
+        // a 4:1 banner, as from_file_at_scale would return it
+        let (_, w, h) = fit_icon(ICON_SIZE, ICON_SIZE / 4, radius);
+        assert_eq!((w, h), (78.0, 19.5));
+        assert!(w.hypot(h) / 2.0 <= radius);
+
+        // a tall icon smaller than ICON_SIZE still fills the same extent
+        let (_, w, h) = fit_icon(64, 128, radius);
+        assert_eq!((w, h), (39.0, 78.0));
+    }
+
+    #[test]
+    fn test_truncate_to_fit_keeps_graphemes_whole() {
+        // one unit per grapheme, so widths are easy to reason about
+        let width = |s: &str| s.graphemes(true).count() as f64;
+
+        assert_eq!(truncate_to_fit("Zen", 3.0, width), "Zen");
+        assert_eq!(truncate_to_fit("Visual Studio Code", 7.0, width), "Visual…");
+        // wide CJK and combining sequences are cut between graphemes, never inside one
+        assert_eq!(truncate_to_fit("日本語のアプリ", 4.0, width), "日本語…");
+        assert_eq!(
+            truncate_to_fit("e\u{301}e\u{301}e\u{301}", 2.0, width),
+            "e\u{301}…"
+        );
+        // nothing fits but the ellipsis
+        assert_eq!(truncate_to_fit("Firefox", 0.5, width), "…");
+    }
+
+    #[test]
+    fn test_wrap_to_fit_breaks_on_spaces() {
+        let width = |s: &str| s.chars().count() as f64;
+
+        assert_eq!(wrap_to_fit("Zen Browser", 20.0, 2, width), ["Zen Browser"]);
+        assert_eq!(
+            wrap_to_fit("No slots  to\tshow", 8.0, 3, width),
+            ["No slots", "to show"]
+        );
+        // the rest of the text is squeezed onto the last line
+        assert_eq!(
+            wrap_to_fit("Visual Studio Code", 10.0, 2, width),
+            ["Visual", "Studio Co…"]
+        );
+        assert_eq!(
+            wrap_to_fit("Supercalifragilistic app", 5.0, 2, width),
+            ["Supe…", "app"]
+        );
+        assert!(wrap_to_fit("   ", 5.0, 2, width).is_empty());
+    }
 }
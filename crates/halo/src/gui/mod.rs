@@ -1,4 +1,5 @@
 pub mod app;
+pub mod confine;
 pub mod menu;
 pub mod theme;
 pub mod window;
@@ -1,3 +1,4 @@
+use crate::config::{ColorValue, MenuColors};
 use gtk::gdk;
 use gtk::prelude::*;
 use gtk4 as gtk;
@@ -9,6 +10,11 @@ pub struct ThemeColors {
     pub default: Srgba<f64>,
     pub center_circle: Srgba<f64>,
     pub broken: Srgba<f64>,
+    /// Slots whose window asked for attention
+    pub urgent: Srgba<f64>,
+    /// Keycap and keybind hint text, and the shadow that lifts it off the icon
+    pub badge_text: Srgba<f64>,
+    pub badge_shadow: Srgba<f64>,
 }
 
 impl ThemeColors {
@@ -32,6 +38,12 @@ impl ThemeColors {
                 Srgba::new(0.8, 0.2, 0.2, 0.5),
                 Some(0.5),
             ),
+            urgent: Self::lookup_color(
+                context,
+                "warning_bg_color",
+                Srgba::new(0.9, 0.6, 0.1, 0.7),
+                Some(0.7),
+            ),
             default: Self::lookup_color(
                 context,
                 "theme_bg_color",
@@ -44,9 +56,36 @@ impl ThemeColors {
                 Srgba::new(0.2, 0.2, 0.2, 0.15),
                 Some(0.1),
             ),
+            badge_text: Self::lookup_color(
+                context,
+                "theme_fg_color",
+                Srgba::new(1.0, 1.0, 1.0, 0.6),
+                Some(0.6),
+            ),
+            badge_shadow: Self::lookup_color(
+                context,
+                "theme_bg_color",
+                Srgba::new(0.0, 0.0, 0.0, 0.5),
+                Some(0.5),
+            ),
         }
     }
 
+    /// Replaces the theme's colors with those set in the config.
+    pub fn with_overrides(mut self, overrides: &MenuColors) -> Self {
+        let apply = |color: &mut Srgba<f64>, value: Option<ColorValue>| {
+            if let Some(ColorValue(value)) = value {
+                *color = value;
+            }
+        };
+        apply(&mut self.hovered, overrides.hovered);
+        apply(&mut self.running, overrides.running);
+        apply(&mut self.default, overrides.default);
+        apply(&mut self.center_circle, overrides.center_circle);
+        apply(&mut self.broken, overrides.broken);
+        self
+    }
+
     fn lookup_color(
         context: &gtk::StyleContext,
         name: &str,
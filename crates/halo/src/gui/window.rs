@@ -40,3 +40,44 @@ pub fn set_window_monitor(window: &gtk::ApplicationWindow, monitor_name: &Monito
         window.set_monitor(Some(&monitor));
     }
 }
+
+/// Restricts pointer input to a circle so clicks outside it reach the windows underneath.
+/// Wayland input regions are rectangle unions, so the circle is built from thin strips.
+pub fn set_input_circle(window: &gtk::ApplicationWindow, center: Point, radius: f64) {
+    const STRIP: i32 = 4;
+
+    WidgetExt::realize(window);
+    let Some(surface) = window.surface() else {
+        return;
+    };
+
+    let region = cairo::Region::create();
+    let r = radius.ceil() as i32;
+    let (cx, cy) = (center.x.round() as i32, center.y.round() as i32);
+    for top in (-r..r).step_by(STRIP as usize) {
+        // widest point of the circle within this strip
+        let dy = if top < 0 && top + STRIP > 0 {
+            0
+        } else {
+            top.abs().min((top + STRIP).abs())
+        };
+        let half = (radius.powi(2) - (dy as f64).powi(2))
+            .max(0.0)
+            .sqrt()
+            .ceil() as i32;
+        let _ = region.union_rectangle(&cairo::RectangleInt::new(
+            cx - half,
+            cy + top,
+            half * 2,
+            STRIP,
+        ));
+    }
+    surface.set_input_region(&region);
+}
+
+pub fn clear_input_region(window: &gtk::ApplicationWindow) {
+    if let Some(surface) = window.surface() {
+        let full = cairo::RectangleInt::new(0, 0, surface.width(), surface.height());
+        surface.set_input_region(&cairo::Region::create_rectangle(&full));
+    }
+}
@@ -1,29 +1,59 @@
+use clap::{Parser, Subcommand};
 use halo::config;
 use halo::gui::app::AppModel;
 use halo::gui::menu::State;
-use halo::sys::runtime;
+use halo::sys::{doctor, runtime};
 use hypraise::wm::Point;
 use relm4::prelude::*;
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+#[command(name = "halo", version, about, long_about = None)]
+struct Cli {
+    /// Use this config file instead of ~/.config/halo/config.toml
+    #[arg(short = 'c', long)]
+    config: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Option<Commands>,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+enum Commands {
+    /// Check the environment halo needs and print a report for bug reports
+    Doctor,
+}
 
 fn main() {
     env_logger::init();
+    let cli = Cli::parse();
+
+    if let Some(path) = cli.config {
+        config::set_config_path(path);
+    }
+
+    if let Some(Commands::Doctor) = cli.command {
+        std::process::exit(if doctor::run() { 0 } else { 1 });
+    }
 
     let config = config::load_or_setup();
     let slots = State::init_slots(&config);
-    let state = State::new(
+    let mut state = State::new(
         slots,
         Point::default(),
         Vec::new(),
         1.0,
         config.show_active_clients,
     );
+    state.apply_config(&config);
 
     let (tx, rx) = async_channel::bounded(32);
 
     // Start Background Services
-    runtime::start_background_services(tx.clone());
+    runtime::start_background_services(tx.clone(), config.broadcast_events);
 
-    let app = RelmApp::new("org.troia.halo");
+    // arguments are handled above, so GTK shouldn't try to parse them
+    let app = RelmApp::new("org.troia.halo").with_args(Vec::new());
 
-    app.run::<AppModel>((state, tx.clone(), rx));
+    app.run::<AppModel>((state, config, tx.clone(), rx));
 }
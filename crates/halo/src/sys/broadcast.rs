@@ -0,0 +1,116 @@
+use crate::config::Direction;
+use std::fmt;
+use std::sync::OnceLock;
+use tokio::io::AsyncWriteExt;
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::broadcast::{self, error::RecvError};
+
+pub const EVENTS_SOCKET_PATH: &str = "/tmp/halo-events.sock";
+
+/// Lines a slow listener may fall behind by before it starts missing some.
+const BACKLOG: usize = 64;
+
+/// Set once the event socket is up, so nothing is formatted for nobody otherwise.
+static EVENTS: OnceLock<broadcast::Sender<String>> = OnceLock::new();
+
+/// What the menu tells listeners on [`EVENTS_SOCKET_PATH`], one line each.
+#[derive(Debug, PartialEq)]
+pub enum MenuEvent<'a> {
+    /// The hovered slot changed: its direction, `None` in a category ring, and its name.
+    /// A hover of `None` means nothing is hovered any more
+    Hover(Option<(Option<Direction>, &'a str)>),
+    Activate(&'a str),
+}
+
+impl fmt::Display for MenuEvent<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Hover(None) => write!(f, "hover none"),
+            Self::Hover(Some((Some(direction), name))) => write!(f, "hover {direction} {name}"),
+            Self::Hover(Some((None, name))) => write!(f, "hover - {name}"),
+            Self::Activate(name) => write!(f, "activate {name}"),
+        }
+    }
+}
+
+pub fn is_enabled() -> bool {
+    EVENTS.get().is_some()
+}
+
+/// Sends `event`, usually a [`MenuEvent`], to every connected listener; a no-op when there
+/// are none.
+pub fn publish(event: impl fmt::Display) {
+    if let Some(tx) = EVENTS.get()
+        && tx.receiver_count() > 0
+    {
+        let _ = tx.send(event.to_string());
+    }
+}
+
+async fn forward(mut stream: UnixStream, mut rx: broadcast::Receiver<String>) {
+    loop {
+        let line = match rx.recv().await {
+            Ok(line) => line,
+            Err(RecvError::Lagged(missed)) => {
+                log::debug!("Event listener fell behind, skipped {} event(s)", missed);
+                continue;
+            }
+            Err(RecvError::Closed) => return,
+        };
+        if stream
+            .write_all(format!("{line}\n").as_bytes())
+            .await
+            .is_err()
+        {
+            return;
+        }
+    }
+}
+
+/// Serves the read-only event socket. Listeners only ever read; anything they write is
+/// ignored.
+pub async fn run_event_server() {
+    if std::fs::metadata(EVENTS_SOCKET_PATH).is_ok() {
+        let _ = std::fs::remove_file(EVENTS_SOCKET_PATH);
+    }
+
+    let listener = match UnixListener::bind(EVENTS_SOCKET_PATH) {
+        Ok(l) => l,
+        Err(e) => {
+            log::error!("Failed to bind event socket: {}", e);
+            return;
+        }
+    };
+    let tx = EVENTS.get_or_init(|| broadcast::channel(BACKLOG).0);
+
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                tokio::spawn(forward(stream, tx.subscribe()));
+            }
+            Err(e) => {
+                log::error!("Failed to accept event listener: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_lines() {
+        let hover = MenuEvent::Hover(Some((Some(Direction::NorthEast), "Zen Browser")));
+        assert_eq!(hover.to_string(), "hover NorthEast Zen Browser");
+        assert_eq!(
+            MenuEvent::Hover(Some((None, "Kate"))).to_string(),
+            "hover - Kate"
+        );
+        assert_eq!(MenuEvent::Hover(None).to_string(), "hover none");
+        assert_eq!(
+            MenuEvent::Activate("ghostty").to_string(),
+            "activate ghostty"
+        );
+    }
+}
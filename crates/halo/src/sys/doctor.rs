@@ -0,0 +1,149 @@
+use crate::config;
+use crate::gui::window;
+use crate::sys::server::SOCKET_PATH;
+use gtk::prelude::*;
+use gtk4 as gtk;
+use hypraise::{desktop, wm};
+use std::os::unix::net::UnixStream;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Ok,
+    Warn,
+    Fail,
+}
+
+impl Status {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Ok => "[ok]",
+            Self::Warn => "[warn]",
+            Self::Fail => "[FAIL]",
+        }
+    }
+}
+
+type Check = (Status, String);
+
+/// Prints one line per check for pasting into bug reports. Returns `false` if anything
+/// halo can't run without failed.
+pub fn run() -> bool {
+    // the monitor check goes through GTK, so it has to come after GTK is initialized
+    let checks = [
+        ("hyprland", hyprland()),
+        ("config", config()),
+        ("desktop entries", desktop_entries()),
+        ("daemon", daemon()),
+        ("gtk", gtk()),
+        ("monitor", monitor()),
+    ];
+    println!("halo {}", env!("CARGO_PKG_VERSION"));
+    for (name, (status, detail)) in &checks {
+        println!("{:<6} {}: {}", status.label(), name, detail);
+    }
+    checks
+        .iter()
+        .all(|(_, (status, _))| *status != Status::Fail)
+}
+
+fn hyprland() -> Check {
+    match std::env::var("HYPRLAND_INSTANCE_SIGNATURE") {
+        Ok(signature) => (Status::Ok, signature),
+        Err(_) => (
+            Status::Fail,
+            "HYPRLAND_INSTANCE_SIGNATURE is unset, is Hyprland running?".to_string(),
+        ),
+    }
+}
+
+fn config() -> Check {
+    let path = match config::get_config_path() {
+        Ok(path) => path,
+        Err(e) => return (Status::Fail, e.to_string()),
+    };
+    if !path.exists() {
+        return (
+            Status::Ok,
+            format!("{} (missing, halo starts in setup mode)", path.display()),
+        );
+    }
+    match config::load_config() {
+        Ok(config) => {
+            let summary = match config::disabled_slots(&config.slots) {
+                0 => format!("{}, {} slot(s)", path.display(), config.slots.len()),
+                disabled => format!(
+                    "{}, {} slot(s), {disabled} disabled",
+                    path.display(),
+                    config.slots.len()
+                ),
+            };
+            match config::ignored_slots(&config.slots).len() {
+                0 => (Status::Ok, summary),
+                n => (
+                    Status::Warn,
+                    format!("{summary}, {n} ignored (see the log)"),
+                ),
+            }
+        }
+        Err(e) => (Status::Fail, format!("{}: {}", path.display(), e)),
+    }
+}
+
+fn desktop_entries() -> Check {
+    match desktop::scan_entries().len() {
+        0 => (Status::Warn, "none found in the XDG data dirs".to_string()),
+        n => (Status::Ok, format!("{n} found")),
+    }
+}
+
+fn daemon() -> Check {
+    match UnixStream::connect(SOCKET_PATH) {
+        Ok(_) => (Status::Ok, format!("listening on {SOCKET_PATH}")),
+        Err(e) => (Status::Warn, format!("not reachable at {SOCKET_PATH}: {e}")),
+    }
+}
+
+fn gtk() -> Check {
+    if let Err(e) = gtk::init() {
+        return (Status::Fail, e.to_string());
+    }
+    let version = format!(
+        "GTK {}.{}.{}",
+        gtk::major_version(),
+        gtk::minor_version(),
+        gtk::micro_version()
+    );
+    if gtk4_layer_shell::is_supported() {
+        (Status::Ok, format!("{version}, layer shell supported"))
+    } else {
+        (
+            Status::Fail,
+            format!("{version}, but the compositor doesn't support layer shell"),
+        )
+    }
+}
+
+fn monitor() -> Check {
+    let Some(name) = wm::get_active_monitor() else {
+        return (
+            Status::Warn,
+            "couldn't query the active monitor".to_string(),
+        );
+    };
+    match window::get_monitor_by_name(&name) {
+        Some(monitor) => {
+            let geometry = monitor.geometry();
+            (
+                Status::Ok,
+                format!(
+                    "{}, {}x{} at scale {}",
+                    name,
+                    geometry.width(),
+                    geometry.height(),
+                    monitor.scale_factor()
+                ),
+            )
+        }
+        None => (Status::Warn, format!("{name}, but GTK doesn't know it")),
+    }
+}
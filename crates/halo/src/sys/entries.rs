@@ -0,0 +1,101 @@
+use crate::events::AppEvent;
+use async_channel::Sender;
+use hypraise::desktop;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::time::Duration;
+
+/// How long the applications directories must stay quiet before rescanning, since a package
+/// install writes many entries in a row.
+const RESCAN_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Whether `event` added, removed or changed a desktop entry.
+fn touches_entries(event: &Event) -> bool {
+    matches!(
+        event.kind,
+        EventKind::Modify(_) | EventKind::Create(_) | EventKind::Remove(_)
+    ) && event
+        .paths
+        .iter()
+        .any(|p| p.extension().is_some_and(|ext| ext == "desktop"))
+}
+
+/// Rescans the desktop entries whenever one changes in a watched applications directory, then
+/// sends [`AppEvent::EntriesChanged`]. Directories that don't exist are skipped; many systems
+/// only have some of them.
+pub async fn run_entry_watcher(tx: Sender<AppEvent>) {
+    let (bridge_tx, bridge_rx) = async_channel::unbounded();
+
+    let mut watcher = match RecommendedWatcher::new(
+        move |res| {
+            let _ = bridge_tx.send_blocking(res);
+        },
+        notify::Config::default(),
+    ) {
+        Ok(w) => w,
+        Err(e) => {
+            log::error!("Failed to create desktop entry watcher: {}", e);
+            return;
+        }
+    };
+
+    let mut watched = 0;
+    for dir in desktop::get_desktop_directories() {
+        if !dir.is_dir() {
+            continue;
+        }
+        match watcher.watch(&dir, RecursiveMode::NonRecursive) {
+            Ok(()) => watched += 1,
+            Err(e) => log::warn!("Failed to watch {}: {}", dir.display(), e),
+        }
+    }
+    if watched == 0 {
+        log::warn!("No applications directory to watch, new apps need `hypraise rescan`");
+        return;
+    }
+
+    while let Ok(res) = bridge_rx.recv().await {
+        match res {
+            Ok(event) if touches_entries(&event) => {
+                // wait out the rest of the burst
+                while let Ok(Ok(_)) = tokio::time::timeout(RESCAN_DEBOUNCE, bridge_rx.recv()).await
+                {
+                }
+                let _ = tokio::task::spawn_blocking(desktop::refresh_cache).await;
+                if tx.send(AppEvent::EntriesChanged).await.is_err() {
+                    break;
+                }
+            }
+            Ok(_) => {}
+            Err(e) => log::error!("Desktop entry watch error: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{CreateKind, ModifyKind};
+
+    #[test]
+    fn test_touches_entries() {
+        let event = |kind, path: &str| Event::new(kind).add_path(path.into());
+
+        assert!(touches_entries(&event(
+            EventKind::Create(CreateKind::File),
+            "/usr/share/applications/firefox.desktop"
+        )));
+        assert!(touches_entries(&event(
+            EventKind::Modify(ModifyKind::Any),
+            "/usr/share/applications/firefox.desktop"
+        )));
+        // package managers write temp files next to the entries
+        assert!(!touches_entries(&event(
+            EventKind::Create(CreateKind::File),
+            "/usr/share/applications/.firefox.desktop.dpkg-new"
+        )));
+        assert!(!touches_entries(&event(
+            EventKind::Access(notify::event::AccessKind::Any),
+            "/usr/share/applications/firefox.desktop"
+        )));
+    }
+}
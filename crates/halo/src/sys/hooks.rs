@@ -0,0 +1,80 @@
+use hypraise::desktop::{Exec, ExecCommand};
+use hypraise::wm;
+use std::process::{Child, ExitStatus};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+/// Longest a `pre_exec` hook may run before the activation is given up on.
+pub const HOOK_TIMEOUT: Duration = Duration::from_secs(5);
+const HOOK_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+#[derive(Debug, Error)]
+pub enum HookError {
+    #[error("failed to start: {0}")]
+    Spawn(#[from] std::io::Error),
+    #[error("exited with {0}")]
+    Failed(ExitStatus),
+    #[error("still running after {0:?}, killed it")]
+    TimedOut(Duration),
+}
+
+fn spawn(command: &str) -> std::io::Result<Child> {
+    let mut command = Exec::from(ExecCommand::new(command))
+        .to_command()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidInput, "empty command"))?;
+    wm::spawn_detached(&mut command)
+}
+
+/// Runs `command` through `sh -c` and waits for it to succeed, killing it after `timeout`.
+pub fn run_hook(command: &str, timeout: Duration) -> Result<(), HookError> {
+    let mut child = spawn(command)?;
+    let started = Instant::now();
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return if status.success() {
+                Ok(())
+            } else {
+                Err(HookError::Failed(status))
+            };
+        }
+        if started.elapsed() >= timeout {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(HookError::TimedOut(timeout));
+        }
+        std::thread::sleep(HOOK_POLL_INTERVAL);
+    }
+}
+
+/// Starts `command` through `sh -c` without waiting for it.
+pub fn spawn_hook(command: &str) {
+    match spawn(command) {
+        // reaped in the background so it doesn't linger as a zombie
+        Ok(mut child) => {
+            std::thread::spawn(move || child.wait());
+        }
+        Err(e) => log::error!("Failed to run '{}': {}", command, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_hook() {
+        assert!(run_hook("true", HOOK_TIMEOUT).is_ok());
+        assert!(matches!(
+            run_hook("exit 3", HOOK_TIMEOUT),
+            Err(HookError::Failed(status)) if status.code() == Some(3)
+        ));
+        assert!(matches!(
+            run_hook("sleep 5", Duration::from_millis(50)),
+            Err(HookError::TimedOut(_))
+        ));
+        assert!(matches!(
+            run_hook(" ", HOOK_TIMEOUT),
+            Err(HookError::Spawn(_))
+        ));
+    }
+}
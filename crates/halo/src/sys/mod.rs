@@ -1,2 +1,7 @@
+pub mod broadcast;
+pub mod doctor;
+pub mod entries;
+pub mod hooks;
 pub mod runtime;
 pub mod server;
+pub mod usage;
@@ -1,9 +1,73 @@
 use crate::events::AppEvent;
 use async_channel::Sender;
+use hypraise::wm::{self, ActiveClient};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::thread;
+use std::time::Duration;
 use tokio::runtime::Builder;
 
-pub fn start_background_services(tx: Sender<AppEvent>) {
+const CLIENT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Set while the menu is open with `live_client_updates` on.
+static POLL_CLIENTS: AtomicBool = AtomicBool::new(false);
+
+/// The clients the menu opened with, which the first poll compares against.
+static OPENED_WITH: Mutex<Option<Vec<ClientKey>>> = Mutex::new(None);
+
+type ClientKey = (String, String, String);
+
+/// Starts or stops polling Hyprland's clients for [`AppEvent::ClientsChanged`].
+pub fn set_client_polling(enabled: bool) {
+    POLL_CLIENTS.store(enabled, Ordering::Relaxed);
+}
+
+/// Records the clients the menu was just shown with, so a window that opens or closes
+/// before the first poll still counts as a change.
+pub fn set_polled_clients(clients: &[ActiveClient]) {
+    *OPENED_WITH.lock().unwrap_or_else(|e| e.into_inner()) = Some(client_key(clients));
+}
+
+/// The parts of the client list worth redrawing for; titles change too often to count.
+fn client_key(clients: &[ActiveClient]) -> Vec<ClientKey> {
+    clients
+        .iter()
+        .map(|c| {
+            (
+                c.address.to_string(),
+                c.class.to_string(),
+                c.workspace_name.clone(),
+            )
+        })
+        .collect()
+}
+
+async fn poll_clients(tx: Sender<AppEvent>) {
+    let mut interval = tokio::time::interval(CLIENT_POLL_INTERVAL);
+    // the clients as of the last poll, or as the menu opened with
+    let mut last = None;
+    loop {
+        interval.tick().await;
+        if !POLL_CLIENTS.load(Ordering::Relaxed) {
+            last = None;
+            continue;
+        }
+        let Ok(clients) = tokio::task::spawn_blocking(wm::get_active_clients).await else {
+            continue;
+        };
+        if last.is_none() {
+            last = OPENED_WITH.lock().unwrap_or_else(|e| e.into_inner()).take();
+        }
+        let key = client_key(&clients);
+        if last.as_ref().is_some_and(|last| *last != key) {
+            let _ = tx.send(AppEvent::ClientsChanged(clients)).await;
+        }
+        last = Some(key);
+    }
+}
+
+/// Starts the socket server, watchers and pollers, plus the event socket if `broadcast_events`.
+pub fn start_background_services(tx: Sender<AppEvent>, broadcast_events: bool) {
     thread::spawn(move || {
         let rt = Builder::new_current_thread()
             .enable_all()
@@ -11,6 +75,11 @@ pub fn start_background_services(tx: Sender<AppEvent>) {
             .expect("Failed to create Tokio runtime");
 
         rt.block_on(async {
+            // the services send through the coalescer so a burst can't block them
+            let (events_tx, events_rx) = async_channel::bounded(crate::events::MAX_PENDING);
+            tokio::spawn(crate::events::coalesce(events_rx, tx));
+            let tx = events_tx;
+
             {
                 let tx = tx.clone();
                 tokio::spawn(async move {
@@ -25,6 +94,30 @@ pub fn start_background_services(tx: Sender<AppEvent>) {
                 });
             }
 
+            {
+                let tx = tx.clone();
+                tokio::spawn(poll_clients(tx));
+            }
+
+            if broadcast_events {
+                tokio::spawn(crate::sys::broadcast::run_event_server());
+            }
+
+            {
+                let tx = tx.clone();
+                tokio::spawn(crate::sys::entries::run_entry_watcher(tx));
+            }
+
+            // Hyprland only announces urgency as an event, so it has to be watched all along
+            tokio::task::spawn_blocking(|| {
+                if let Err(e) = wm::watch_urgent() {
+                    log::warn!("Stopped tracking urgent windows: {}", e);
+                }
+            });
+
+            // resolving the slots usually scans already, but not when no slot names an app
+            tokio::task::spawn_blocking(hypraise::desktop::warm_cache);
+
             std::future::pending::<()>().await;
         });
     });
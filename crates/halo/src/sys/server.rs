@@ -1,9 +1,229 @@
-use crate::events::AppEvent;
+use crate::config::{ConfigTemplate, Direction};
+use crate::events::{AppEvent, Placement};
 use async_channel::Sender;
-use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::net::UnixListener;
+use hypraise::desktop::AppQuery;
+use hypraise::wm::Point;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
 
-const SOCKET_PATH: &str = "/tmp/halo.sock";
+pub const SOCKET_PATH: &str = "/tmp/halo.sock";
+
+/// Longest accepted request line. Commands are short, so anything longer is garbage.
+const MAX_LINE_LEN: usize = 4096;
+
+/// A request after parsing, before it's turned into events.
+#[derive(Debug, PartialEq)]
+enum Command {
+    Show,
+    ShowAt(Placement),
+    /// Show only the slots whose app is running
+    ShowRunning,
+    /// Show the apps in a desktop entry category instead of the slots
+    ShowCategory(String),
+    /// Show until the connection closes
+    Hold,
+    Switch,
+    Hide,
+    RefreshIcons,
+    Rescan,
+    /// Open the config, as the setup slot does
+    Setup,
+    Subslots,
+    /// Reply with the config in effect, runtime overrides included
+    DumpConfig,
+    /// Reply with a config holding a slot for each open app
+    ExportConfig,
+    SetSlot(Direction, AppQuery),
+}
+
+/// Splits a request into its verb and arguments and checks the arguments. The error is the
+/// message sent back to the client.
+fn parse_command(line: &str) -> Result<Command, String> {
+    let line = line.trim();
+    let (verb, args) = line
+        .split_once(char::is_whitespace)
+        .map_or((line, ""), |(verb, args)| (verb, args.trim()));
+
+    let command = match verb {
+        "show-running" => Command::ShowRunning,
+        "hold" => Command::Hold,
+        "switch" => Command::Switch,
+        "hide" => Command::Hide,
+        "refresh-icons" => Command::RefreshIcons,
+        "rescan" => Command::Rescan,
+        "setup" => Command::Setup,
+        "subslots" => Command::Subslots,
+        "dump-config" => Command::DumpConfig,
+        "export-config" => Command::ExportConfig,
+        "show" => return parse_show(args),
+        "show-category" if args.is_empty() => {
+            return Err("usage: show-category <category>".to_string());
+        }
+        "show-category" => return Ok(Command::ShowCategory(args.to_string())),
+        "set-slot" => return parse_set_slot(args),
+        _ => return Err(format!("unknown command '{verb}'")),
+    };
+    if args.is_empty() {
+        Ok(command)
+    } else {
+        Err(format!("'{verb}' takes no arguments"))
+    }
+}
+
+/// Parses the options of `show [--at <x>,<y>] [--scale <factor>]`.
+fn parse_show(args: &str) -> Result<Command, String> {
+    const USAGE: &str = "usage: show [--at <x>,<y>] [--scale <factor>]";
+    let mut placement = Placement::default();
+    let mut words = args.split_whitespace();
+    while let Some(option) = words.next() {
+        let value = words.next().ok_or(USAGE)?;
+        match option {
+            "--at" => {
+                let coordinate = |c: &str| c.parse::<f64>().ok().filter(|c| c.is_finite());
+                let at = value
+                    .split_once(',')
+                    .and_then(|(x, y)| Some(Point::new(coordinate(x)?, coordinate(y)?)));
+                placement.at = Some(at.ok_or_else(|| format!("invalid position '{value}'"))?);
+            }
+            "--scale" => {
+                let scale = value
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|s| s.is_finite() && *s > 0.0);
+                placement.scale = Some(scale.ok_or_else(|| format!("invalid scale '{value}'"))?);
+            }
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+    Ok(if placement == Placement::default() {
+        Command::Show
+    } else {
+        Command::ShowAt(placement)
+    })
+}
+
+/// Parses the arguments of `set-slot <direction> <app query>`; the query may contain spaces.
+fn parse_set_slot(args: &str) -> Result<Command, String> {
+    const USAGE: &str = "usage: set-slot <direction> <app>";
+    let (direction, query) = args.split_once(char::is_whitespace).ok_or(USAGE)?;
+    let direction = direction
+        .parse::<Direction>()
+        .map_err(|_| format!("unknown direction '{direction}'"))?;
+    let query = query.trim();
+    if query.is_empty() {
+        return Err(USAGE.to_string());
+    }
+    Ok(Command::SetSlot(direction, AppQuery::new(query)))
+}
+
+#[derive(Debug, PartialEq)]
+enum LineError {
+    TooLong,
+    NotUtf8,
+}
+
+/// Reads one line of at most [`MAX_LINE_LEN`] bytes, without its newline. `Ok(None)` at EOF.
+async fn read_line(
+    reader: &mut (impl AsyncBufRead + Unpin),
+) -> std::io::Result<Option<Result<String, LineError>>> {
+    let mut buf = Vec::new();
+    let read = reader
+        .take(MAX_LINE_LEN as u64 + 1)
+        .read_until(b'\n', &mut buf)
+        .await?;
+    if read == 0 {
+        return Ok(None);
+    }
+    if buf.last() == Some(&b'\n') {
+        buf.pop();
+    } else if buf.len() > MAX_LINE_LEN {
+        return Ok(Some(Err(LineError::TooLong)));
+    }
+    Ok(Some(String::from_utf8(buf).map_err(|_| LineError::NotUtf8)))
+}
+
+async fn handle_connection(mut stream: UnixStream, tx: Sender<AppEvent>) {
+    let (reader, mut writer) = stream.split();
+    let mut reader = BufReader::new(reader);
+    let mut held = false;
+
+    while let Ok(Some(line)) = read_line(&mut reader).await {
+        let reply = match line {
+            Ok(line) if line.trim().is_empty() => continue,
+            Ok(line) => match parse_command(&line) {
+                Ok(command) => dispatch(command, &tx, &mut held).await,
+                Err(e) => Some(format!("err: {e}")),
+            },
+            Err(LineError::NotUtf8) => Some("err: invalid utf-8".to_string()),
+            Err(LineError::TooLong) => {
+                // the rest of the line is still unread, so there's no telling where the next
+                // request starts
+                let _ = writer.write_all(b"err: line too long\n").await;
+                break;
+            }
+        };
+        if let Some(reply) = reply {
+            let _ = writer.write_all(format!("{reply}\n").as_bytes()).await;
+        }
+    }
+
+    if held {
+        let _ = tx.send(AppEvent::Hide).await;
+    }
+}
+
+/// Runs a command, returning the reply line if it has one.
+async fn dispatch(command: Command, tx: &Sender<AppEvent>, held: &mut bool) -> Option<String> {
+    let event = match command {
+        Command::Show => AppEvent::Show,
+        Command::ShowAt(placement) => AppEvent::ShowAt(placement),
+        Command::ShowRunning => AppEvent::ShowRunning,
+        Command::ShowCategory(category) => AppEvent::ShowCategory(category),
+        Command::Hold => {
+            // shown for as long as the client keeps this connection open
+            *held = true;
+            AppEvent::Show
+        }
+        Command::Switch => AppEvent::Switch,
+        Command::Hide => AppEvent::Hide,
+        Command::RefreshIcons => AppEvent::RefreshIcons,
+        Command::Rescan => {
+            // scan here rather than on the GUI thread, which only re-resolves the slots
+            let _ = tokio::task::spawn_blocking(hypraise::desktop::refresh_cache).await;
+            AppEvent::EntriesChanged
+        }
+        Command::Setup => {
+            let _ = tokio::task::spawn_blocking(crate::config::open_setup).await;
+            return None;
+        }
+        Command::SetSlot(direction, query) => AppEvent::SetSlot(direction, query),
+        Command::Subslots => {
+            let (reply_tx, reply_rx) = async_channel::bounded(1);
+            let _ = tx.send(AppEvent::DumpSubslots(reply_tx)).await;
+            return reply_rx.recv().await.ok();
+        }
+        Command::DumpConfig => {
+            let (reply_tx, reply_rx) = async_channel::bounded(1);
+            let _ = tx.send(AppEvent::DumpConfig(reply_tx)).await;
+            return reply_rx.recv().await.ok();
+        }
+        Command::ExportConfig => {
+            let template = tokio::task::spawn_blocking(|| {
+                let clients = hypraise::wm::get_active_clients();
+                ConfigTemplate::from_clients(&clients, hypraise::desktop::find_desktop_entry)
+                    .to_toml()
+            })
+            .await;
+            return Some(match template {
+                Ok(Ok(toml)) => toml,
+                Ok(Err(e)) => format!("err: {e}"),
+                Err(e) => format!("err: {e}"),
+            });
+        }
+    };
+    let _ = tx.send(event).await;
+    None
+}
 
 pub async fn run_server(tx: Sender<AppEvent>) {
     // Cleanup old socket if it exists
@@ -21,24 +241,8 @@ pub async fn run_server(tx: Sender<AppEvent>) {
 
     loop {
         match listener.accept().await {
-            Ok((mut stream, _)) => {
-                let tx = tx.clone();
-                tokio::spawn(async move {
-                    let reader = BufReader::new(&mut stream);
-                    let mut lines = reader.lines();
-
-                    while let Ok(Some(line)) = lines.next_line().await {
-                        match line.trim() {
-                            "show" => {
-                                let _ = tx.send(AppEvent::Show).await;
-                            }
-                            "hide" => {
-                                let _ = tx.send(AppEvent::Hide).await;
-                            }
-                            _ => {}
-                        }
-                    }
-                });
+            Ok((stream, _)) => {
+                tokio::spawn(handle_connection(stream, tx.clone()));
             }
             Err(e) => {
                 log::error!("Failed to accept connection: {}", e);
@@ -46,3 +250,97 @@ pub async fn run_server(tx: Sender<AppEvent>) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_set_slot() {
+        assert_eq!(
+            parse_command("set-slot ne Zen Browser"),
+            Ok(Command::SetSlot(
+                Direction::NorthEast,
+                AppQuery::new("Zen Browser")
+            ))
+        );
+
+        assert!(parse_command("set-slot north").is_err());
+        assert!(parse_command("set-slot up firefox").is_err());
+        assert_eq!(parse_command("show"), Ok(Command::Show));
+    }
+
+    #[test]
+    fn test_parse_command_errors() {
+        assert_eq!(parse_command("  hide \r"), Ok(Command::Hide));
+        assert_eq!(parse_command("rescan"), Ok(Command::Rescan));
+        assert_eq!(parse_command("setup"), Ok(Command::Setup));
+        assert_eq!(parse_command("dump-config"), Ok(Command::DumpConfig));
+        assert_eq!(parse_command("export-config"), Ok(Command::ExportConfig));
+        assert_eq!(
+            parse_command("show-category  Development"),
+            Ok(Command::ShowCategory("Development".to_string()))
+        );
+        assert!(parse_command("show-category").is_err());
+        assert_eq!(parse_command("show-running"), Ok(Command::ShowRunning));
+        assert_eq!(
+            parse_command("toggle"),
+            Err("unknown command 'toggle'".to_string())
+        );
+        assert_eq!(
+            parse_command("hide now"),
+            Err("'hide' takes no arguments".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_show_placement() {
+        assert_eq!(
+            parse_command("show --at 1820,30 --scale 0.7"),
+            Ok(Command::ShowAt(Placement {
+                at: Some(Point::new(1820.0, 30.0)),
+                scale: Some(0.7),
+            }))
+        );
+        assert_eq!(
+            parse_command("show --scale 1.5"),
+            Ok(Command::ShowAt(Placement {
+                at: None,
+                scale: Some(1.5),
+            }))
+        );
+        assert!(parse_command("show now").is_err());
+        assert!(parse_command("show --at 10").is_err());
+        assert!(parse_command("show --at 10,y").is_err());
+        assert!(parse_command("show --scale 0").is_err());
+        assert!(parse_command("show --scale").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_read_line_caps_length() {
+        let long = "x".repeat(MAX_LINE_LEN + 10);
+        let input = format!("show\n\u{fffd}\n{long}\nhide\n");
+        let mut input = input.as_bytes();
+
+        assert_eq!(
+            read_line(&mut input).await.unwrap(),
+            Some(Ok("show".into()))
+        );
+        assert_eq!(
+            read_line(&mut input).await.unwrap(),
+            Some(Ok("\u{fffd}".into()))
+        );
+        assert_eq!(
+            read_line(&mut input).await.unwrap(),
+            Some(Err(LineError::TooLong))
+        );
+
+        let mut binary: &[u8] = b"\xff\xfe\n";
+        assert_eq!(
+            read_line(&mut binary).await.unwrap(),
+            Some(Err(LineError::NotUtf8))
+        );
+        let mut empty: &[u8] = b"";
+        assert_eq!(read_line(&mut empty).await.unwrap(), None);
+    }
+}
@@ -0,0 +1,84 @@
+use directories::ProjectDirs;
+use hypraise::wm::WindowClass;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// How many times each app was launched from the menu, keyed by lowercased window class.
+/// Stored as `<count>\t<class>` lines in the cache dir, since losing it only resets sizes.
+#[derive(Debug, Default)]
+pub struct UsageStats {
+    counts: HashMap<String, u64>,
+    path: Option<PathBuf>,
+}
+
+impl UsageStats {
+    pub fn load() -> Self {
+        let path = ProjectDirs::from("org", "troia", "halo").map(|d| d.cache_dir().join("usage"));
+        let counts = path
+            .as_ref()
+            .and_then(|p| fs_err::read_to_string(p).ok())
+            .map(|text| parse(&text))
+            .unwrap_or_default();
+        Self { counts, path }
+    }
+
+    pub fn counts(&self) -> &HashMap<String, u64> {
+        &self.counts
+    }
+
+    /// Counts a launch of `class` and writes the stats back out.
+    pub fn record(&mut self, class: &WindowClass) {
+        *self.counts.entry(class.to_lowercase()).or_default() += 1;
+        if let Some(path) = &self.path
+            && let Err(e) = self.save(path)
+        {
+            log::warn!("Failed to save usage stats: {}", e);
+        }
+    }
+
+    fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs_err::create_dir_all(parent)?;
+        }
+        fs_err::write(path, serialize(&self.counts))
+    }
+}
+
+fn parse(text: &str) -> HashMap<String, u64> {
+    text.lines()
+        .filter_map(|line| {
+            let (count, class) = line.split_once('\t')?;
+            Some((class.to_string(), count.parse().ok()?))
+        })
+        .collect()
+}
+
+fn serialize(counts: &HashMap<String, u64>) -> String {
+    let mut entries: Vec<_> = counts.iter().collect();
+    entries.sort();
+    entries
+        .into_iter()
+        .map(|(class, count)| format!("{count}\t{class}\n"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage_round_trips() {
+        let mut stats = UsageStats::default();
+        stats.record(&WindowClass::new("Firefox"));
+        stats.record(&WindowClass::new("firefox"));
+        stats.record(&WindowClass::new("org.kde.dolphin"));
+
+        let text = serialize(stats.counts());
+        assert_eq!(text, "2\tfirefox\n1\torg.kde.dolphin\n");
+        assert_eq!(&parse(&text), stats.counts());
+
+        // garbled lines are skipped rather than failing the whole file
+        let counts = parse("3\tzen\nnot a count\tx\n\n7");
+        assert_eq!(counts, HashMap::from([("zen".to_string(), 3)]));
+    }
+}
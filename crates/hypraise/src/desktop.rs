@@ -6,6 +6,7 @@ use fs_err as fs;
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
 
@@ -22,6 +23,200 @@ pub struct ExecCommand(String);
 
 crate::impl_string_newtype!(ExecCommand);
 
+/// The `exec` of halo's setup slot. It names no program: activating it opens halo's config
+/// instead, which only the daemon knows how to do.
+pub const SETUP_EXEC: &str = "HALO_SETUP";
+
+/// How to launch an app. Configs may give a plain string, run through `sh -c` like a
+/// desktop entry's `Exec`, or an array that's run directly so arguments need no quoting.
+/// A `{ first_of = [...] }` table of those is a fallback chain: the first one that's
+/// installed runs. It gets its own key since an array of plain strings is already `Args`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Exec {
+    Shell(ExecCommand),
+    Args(Vec<String>),
+    FirstOf { first_of: Vec<Exec> },
+}
+
+impl Exec {
+    /// Whether this is the [`SETUP_EXEC`] sentinel rather than a command.
+    pub fn is_setup(&self) -> bool {
+        matches!(self, Self::Shell(cmd) if cmd.trim() == SETUP_EXEC)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Self::Shell(cmd) => cmd.trim().is_empty(),
+            Self::Args(args) => args.first().is_none_or(|program| program.is_empty()),
+            Self::FirstOf { first_of } => first_of.iter().all(Self::is_empty),
+        }
+    }
+
+    /// The process to spawn, or `None` if there's nothing to run. A bare URI or a path to a
+    /// file that isn't executable is handed to `xdg-open`, since it can't run on its own.
+    pub fn to_command(&self) -> Option<std::process::Command> {
+        let exec = self.resolve()?;
+        if exec.is_empty() {
+            return None;
+        }
+        if let Some(target) = exec.openable_target() {
+            let mut command = std::process::Command::new("xdg-open");
+            command.arg(target);
+            return Some(command);
+        }
+        let command = match exec {
+            Self::Shell(cmd) => {
+                let mut command = std::process::Command::new("sh");
+                command.arg("-c").arg(cmd.as_str());
+                command
+            }
+            Self::Args(args) => {
+                let mut command = std::process::Command::new(&args[0]);
+                command.args(&args[1..]);
+                command
+            }
+            Self::FirstOf { .. } => unreachable!("resolve picks a single command"),
+        };
+        Some(command)
+    }
+
+    /// The command that would run: this one, or for a fallback chain the first candidate whose
+    /// program is on `$PATH`. `None` if none of them are installed.
+    pub fn resolve(&self) -> Option<&Self> {
+        self.resolve_in(&std::env::var_os("PATH").unwrap_or_default())
+    }
+
+    fn resolve_in(&self, path: &OsStr) -> Option<&Self> {
+        match self {
+            Self::FirstOf { first_of } => first_of
+                .iter()
+                .filter_map(|candidate| candidate.resolve_in(path))
+                .find(|candidate| candidate.is_installed(path)),
+            exec => Some(exec),
+        }
+    }
+
+    /// Whether the program this runs exists, checking only the first word of a shell string
+    /// (after any `VAR=value` assignments). Something `xdg-open` handles always counts.
+    fn is_installed(&self, path: &OsStr) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+        if self.openable_target().is_some() {
+            return true;
+        }
+        let program = match self {
+            Self::Shell(cmd) => shell_words::split(cmd)
+                .ok()
+                .and_then(|words| words.into_iter().find(|w| !w.contains('='))),
+            Self::Args(args) => args.first().cloned(),
+            Self::FirstOf { .. } => None,
+        };
+        program.is_some_and(|program| find_program(&program, path).is_some())
+    }
+
+    /// The exec as a single URI like `https://example.com`, or an existing absolute path that
+    /// isn't an executable file, if that's all it is. Relative paths are left alone, as a bare
+    /// program name could also be a directory wherever halo was started.
+    fn openable_target(&self) -> Option<&str> {
+        let target = match self {
+            Self::Shell(cmd) => cmd.trim(),
+            Self::Args(args) if args.len() == 1 => args[0].as_str(),
+            Self::Args(_) | Self::FirstOf { .. } => return None,
+        };
+        if target.contains(char::is_whitespace) {
+            return None;
+        }
+        (has_uri_scheme(target) || (target.starts_with('/') && is_openable_path(Path::new(target))))
+            .then_some(target)
+    }
+
+    /// Replaces `{key}` placeholders with their values; unknown placeholders stay as they are.
+    /// In a shell string the values are quoted, so names with spaces stay one argument.
+    pub fn interpolate(&self, vars: &[(&str, &str)]) -> Self {
+        match self {
+            Self::Shell(cmd) => Self::Shell(ExecCommand::new(substitute(cmd, vars, true))),
+            Self::Args(args) => {
+                Self::Args(args.iter().map(|a| substitute(a, vars, false)).collect())
+            }
+            Self::FirstOf { first_of } => Self::FirstOf {
+                first_of: first_of.iter().map(|c| c.interpolate(vars)).collect(),
+            },
+        }
+    }
+}
+
+/// Where `program` would run from: itself if it's a path, otherwise the first executable
+/// file of that name in one of the `path` directories.
+pub(crate) fn find_program(program: &str, path: &OsStr) -> Option<PathBuf> {
+    use std::os::unix::fs::PermissionsExt;
+    let is_executable = |file: &Path| {
+        fs::metadata(file)
+            .is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+    };
+    if program.contains('/') {
+        let program = PathBuf::from(program);
+        return is_executable(&program).then_some(program);
+    }
+    std::env::split_paths(path)
+        .map(|dir| dir.join(program))
+        .find(|file| is_executable(file))
+}
+
+/// A scheme per RFC 3986 followed by something; one letter would be a Windows drive.
+fn has_uri_scheme(target: &str) -> bool {
+    let Some((scheme, rest)) = target.split_once(':') else {
+        return false;
+    };
+    scheme.len() > 1
+        && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        && !rest.is_empty()
+}
+
+fn is_openable_path(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    match fs::metadata(path) {
+        Ok(meta) if meta.is_dir() => true,
+        Ok(meta) => meta.permissions().mode() & 0o111 == 0,
+        Err(_) => false,
+    }
+}
+
+fn substitute(template: &str, vars: &[(&str, &str)], quote: bool) -> String {
+    vars.iter()
+        .fold(template.to_string(), |text, (key, value)| {
+            let value = if quote {
+                shell_words::quote(value)
+            } else {
+                (*value).into()
+            };
+            text.replace(&format!("{{{key}}}"), &value)
+        })
+}
+
+impl From<ExecCommand> for Exec {
+    fn from(cmd: ExecCommand) -> Self {
+        Self::Shell(cmd)
+    }
+}
+
+impl std::fmt::Display for Exec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Shell(cmd) => write!(f, "{cmd}"),
+            Self::Args(args) => write!(f, "{}", shell_words::join(args)),
+            Self::FirstOf { first_of } => {
+                let candidates: Vec<_> = first_of.iter().map(Self::to_string).collect();
+                write!(f, "{}", candidates.join(" || "))
+            }
+        }
+    }
+}
+
 #[derive(
     Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, Display, Deref, From, Into, AsRef,
 )]
@@ -34,15 +229,33 @@ crate::impl_string_newtype!(AppQuery);
 pub struct AppInfo {
     pub name: AppName,
     pub icon: PathBuf,
+    /// The unresolved `Icon` key (or query) that `icon` was looked up from
+    pub icon_name: IconName,
     pub class: WindowClass,
-    pub exec: ExecCommand,
+    pub exec: Exec,
+    pub startup_notify: bool,
+    /// The desktop entry's file name without `.desktop`, if the app came from one
+    pub id: Option<String>,
+    /// The entry's `Keywords`, lowercased, which queries match after names and classes
+    pub keywords: Vec<String>,
+    /// The entry's `Categories` as written, e.g. `Development`
+    pub categories: Vec<String>,
 }
 
 impl AppInfo {
-    pub fn new(query: &AppQuery, class: Option<WindowClass>, exec: Option<ExecCommand>) -> Self {
-        let base = find_desktop_entry(query);
+    /// Resolves `query` to a desktop entry, with `class` and `exec` overriding the entry's.
+    /// A given `exec` may use the `{name}`, `{class}`, `{id}` and `{query}` placeholders.
+    pub fn new(query: &AppQuery, class: Option<WindowClass>, exec: Option<Exec>) -> Self {
+        Self::with_base(query, class, exec, find_desktop_entry(query))
+    }
 
-        Self {
+    fn with_base(
+        query: &AppQuery,
+        class: Option<WindowClass>,
+        exec: Option<Exec>,
+        base: Option<AppInfo>,
+    ) -> Self {
+        let mut info = Self {
             name: base
                 .as_ref()
                 .map(|b| b.name.clone())
@@ -50,22 +263,84 @@ impl AppInfo {
             icon: base.as_ref().map(|b| b.icon.clone()).unwrap_or_else(|| {
                 icon::find_icon_path(&IconName::from(query.to_string())).unwrap_or_default()
             }),
+            icon_name: base
+                .as_ref()
+                .map(|b| b.icon_name.clone())
+                .unwrap_or_else(|| IconName::from(query.to_string())),
             class: class
                 .or_else(|| base.as_ref().map(|b| b.class.clone()))
                 .unwrap_or_else(|| WindowClass::new(query.to_string())),
-            exec: exec
-                .or_else(|| base.as_ref().map(|b| b.exec.clone()))
-                .unwrap_or_else(|| ExecCommand::new("".to_string())),
+            exec: base
+                .as_ref()
+                .map(|b| b.exec.clone())
+                .unwrap_or_else(|| ExecCommand::new("".to_string()).into()),
+            startup_notify: base.as_ref().is_some_and(|b| b.startup_notify),
+            id: base.as_ref().and_then(|b| b.id.clone()),
+            keywords: base
+                .as_ref()
+                .map(|b| b.keywords.clone())
+                .unwrap_or_default(),
+            categories: base
+                .as_ref()
+                .map(|b| b.categories.clone())
+                .unwrap_or_default(),
+        };
+        if let Some(exec) = exec {
+            info.exec = info.interpolate_exec(&exec, query);
+        }
+        info
+    }
+
+    /// Fills in the placeholders of an `exec` given for this app, which `query` resolved to.
+    pub fn interpolate_exec(&self, exec: &Exec, query: &AppQuery) -> Exec {
+        // without an entry, the query is the closest thing to an id
+        let id = self.id.as_deref().unwrap_or(query.as_str());
+        exec.interpolate(&[
+            ("name", self.name.as_str()),
+            ("class", self.class.as_str()),
+            ("id", id),
+            ("query", query.as_str()),
+        ])
+    }
+
+    /// Builds an `AppInfo` exactly as given, without desktop entry or icon lookups.
+    pub fn from_parts(name: AppName, icon: PathBuf, class: WindowClass, exec: ExecCommand) -> Self {
+        Self {
+            name,
+            icon_name: IconName::new(icon.to_string_lossy()),
+            icon,
+            class,
+            exec: exec.into(),
+            startup_notify: false,
+            id: None,
+            keywords: Vec::new(),
+            categories: Vec::new(),
+        }
+    }
+
+    /// Re-resolves `icon` from `icon_name`, e.g. after the icon theme changed.
+    pub fn reload_icon(&mut self) {
+        if let Some(path) = icon::find_icon_path(&self.icon_name) {
+            self.icon = path;
         }
     }
 }
 
 static ENTRIES: OnceLock<RwLock<Vec<AppInfo>>> = OnceLock::new();
 
+/// Rescans the desktop entries. The scan runs before taking the lock, so readers only wait
+/// for the swap, and never see an empty list if this is the first access.
 pub fn refresh_cache() {
-    let apps = scan_entries();
-    let lock = ENTRIES.get_or_init(|| RwLock::new(Vec::new()));
-    *lock.write() = apps;
+    let mut apps = Some(scan_entries());
+    let lock = ENTRIES.get_or_init(|| RwLock::new(apps.take().unwrap_or_default()));
+    if let Some(apps) = apps {
+        *lock.write() = apps;
+    }
+}
+
+/// Scans the desktop entries if nothing has yet, so the first lookup doesn't have to.
+pub fn warm_cache() {
+    ENTRIES.get_or_init(|| RwLock::new(scan_entries()));
 }
 
 fn get_all_entries() -> Vec<AppInfo> {
@@ -73,7 +348,9 @@ fn get_all_entries() -> Vec<AppInfo> {
     lock.read().clone()
 }
 
-fn get_desktop_directories() -> Vec<PathBuf> {
+/// The `applications` directories desktop entries are read from, highest priority first.
+/// Some may not exist.
+pub fn get_desktop_directories() -> Vec<PathBuf> {
     let xdg = xdg::BaseDirectories::new();
     let mut dirs = Vec::new();
 
@@ -141,29 +418,55 @@ pub fn parse_desktop_file(path: &Path) -> Option<AppInfo> {
     };
 
     let exec_raw = section.attr("Exec").first()?;
-    let exec = strip_field_codes(exec_raw);
+    let exec = strip_field_codes(exec_raw, icon_str.map(|s| s.as_str()));
+
+    let startup_notify = section
+        .attr("StartupNotify")
+        .first()
+        .is_some_and(|v| v == "true");
 
-    let id = path.file_name()?.to_str()?;
+    let id = path.file_name()?.to_str()?.trim_end_matches(".desktop");
     let class = section
         .attr("StartupWMClass")
         .first()
         .cloned()
-        .unwrap_or_else(|| id.trim_end_matches(".desktop").to_string());
+        .unwrap_or_else(|| id.to_string());
 
     Some(AppInfo {
         name: AppName::new(name),
         icon: icon_path,
+        icon_name: IconName::new(icon_str.cloned().unwrap_or_default()),
         class: WindowClass::new(class),
-        exec: ExecCommand::new(exec),
+        exec: ExecCommand::new(exec).into(),
+        startup_notify,
+        id: Some(id.to_string()),
+        keywords: section
+            .attr("Keywords")
+            .first()
+            .map(|k| parse_keywords(k))
+            .unwrap_or_default(),
+        categories: section
+            .attr("Categories")
+            .first()
+            .map(|c| parse_list(c).map(str::to_string).collect())
+            .unwrap_or_default(),
     })
 }
 
-fn strip_field_codes(exec: &str) -> String {
+/// Drops field codes from an `Exec` line. `%i` is the one code that carries meaning without a
+/// file argument, so it expands to `--icon <Icon>` when the entry has an icon.
+fn strip_field_codes(exec: &str, icon: Option<&str>) -> String {
     shell_words::split(exec)
         .map(|args| {
             let clean_args: Vec<_> = args
                 .into_iter()
-                .filter(|arg| !arg.starts_with('%'))
+                .flat_map(|arg| match arg.as_str() {
+                    "%i" => icon
+                        .map(|i| vec!["--icon".to_string(), i.to_string()])
+                        .unwrap_or_default(),
+                    a if a.starts_with('%') => Vec::new(),
+                    _ => vec![arg],
+                })
                 .collect();
             shell_words::join(clean_args)
         })
@@ -174,6 +477,37 @@ pub fn find_desktop_entry(query: &AppQuery) -> Option<AppInfo> {
     find_desktop_entry_in_list(query, &get_all_entries())
 }
 
+/// `Keywords` is a `;`-separated list, usually with a trailing `;`.
+fn parse_keywords(keywords: &str) -> Vec<String> {
+    parse_list(keywords).map(str::to_lowercase).collect()
+}
+
+/// Splits a `;`-separated desktop entry list, skipping empty items.
+fn parse_list(list: &str) -> impl Iterator<Item = &str> {
+    list.split(';')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+}
+
+/// The cached entries listing `category` (ignoring case) in their `Categories`, sorted by
+/// name.
+pub fn entries_in_category(category: &str) -> Vec<AppInfo> {
+    let mut entries = get_all_entries();
+    filter_category(&mut entries, category);
+    entries
+}
+
+fn filter_category(entries: &mut Vec<AppInfo>, category: &str) {
+    entries.retain(|app| {
+        app.categories
+            .iter()
+            .any(|c| c.eq_ignore_ascii_case(category))
+    });
+    entries.sort_by_cached_key(|app| app.name.to_lowercase());
+}
+
+/// Finds the entry whose name or class is `query`, ignoring case, then one with that id, and
+/// failing those, the first that lists it as a keyword.
 pub fn find_desktop_entry_in_list(query: &AppQuery, entries: &[AppInfo]) -> Option<AppInfo> {
     let lower_query = query.to_lowercase();
     entries
@@ -181,9 +515,251 @@ pub fn find_desktop_entry_in_list(query: &AppQuery, entries: &[AppInfo]) -> Opti
         .find(|app| {
             app.name.to_lowercase() == lower_query || app.class.to_lowercase() == lower_query
         })
+        .or_else(|| {
+            entries.iter().find(|app| {
+                app.id
+                    .as_ref()
+                    .is_some_and(|id| id.to_lowercase() == lower_query)
+            })
+        })
+        .or_else(|| {
+            entries
+                .iter()
+                .find(|app| app.keywords.contains(&lower_query))
+        })
         .cloned()
 }
 
 pub fn resolve_apps(queries: &[AppQuery]) -> Vec<Option<AppInfo>> {
     queries.iter().map(find_desktop_entry).collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parts(command: &std::process::Command) -> (String, Vec<String>) {
+        (
+            command.get_program().to_string_lossy().into_owned(),
+            command
+                .get_args()
+                .map(|a| a.to_string_lossy().into_owned())
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_exec_args_skip_the_shell() {
+        let exec = Exec::Args(vec![
+            "flatpak".into(),
+            "run".into(),
+            "org.example.App".into(),
+            "a file; rm -rf ~".into(),
+        ]);
+        let (program, args) = parts(&exec.to_command().unwrap());
+        assert_eq!(program, "flatpak");
+        assert_eq!(args, ["run", "org.example.App", "a file; rm -rf ~"]);
+        assert_eq!(
+            exec.to_string(),
+            "flatpak run org.example.App 'a file; rm -rf ~'"
+        );
+    }
+
+    #[test]
+    fn test_exec_shell_string() {
+        let exec = Exec::from(ExecCommand::new("firefox --new-window"));
+        let (program, args) = parts(&exec.to_command().unwrap());
+        assert_eq!(program, "sh");
+        assert_eq!(args, ["-c", "firefox --new-window"]);
+    }
+
+    #[test]
+    fn test_exec_placeholders_with_entry() {
+        let mut entry = AppInfo::from_parts(
+            AppName::new("Zen Browser"),
+            PathBuf::new(),
+            WindowClass::new("zen"),
+            ExecCommand::new("zen-browser"),
+        );
+        entry.id = Some("zen-browser".into());
+        let query = AppQuery::new("zen");
+
+        let exec = Exec::from(ExecCommand::new("gtk-launch {id} --title {name} {unknown}"));
+        let app = AppInfo::with_base(&query, None, Some(exec), Some(entry.clone()));
+        assert_eq!(
+            app.exec.to_string(),
+            "gtk-launch zen-browser --title 'Zen Browser' {unknown}"
+        );
+
+        let exec = Exec::Args(vec!["myterm".into(), "--class".into(), "{class}".into()]);
+        let class = Some(WindowClass::new("my term"));
+        let app = AppInfo::with_base(&query, class, Some(exec), Some(entry));
+        assert_eq!(
+            app.exec,
+            Exec::Args(vec!["myterm".into(), "--class".into(), "my term".into()])
+        );
+    }
+
+    #[test]
+    fn test_exec_placeholders_without_entry() {
+        let query = AppQuery::new("scratch");
+        let exec = Exec::from(ExecCommand::new("foot --app-id {class} {id}-{query}"));
+        let app = AppInfo::with_base(&query, None, Some(exec), None);
+        assert_eq!(
+            app.exec.to_string(),
+            "foot --app-id scratch scratch-scratch"
+        );
+        assert_eq!(app.id, None);
+    }
+
+    fn entry(name: &str, class: &str, keywords: &[&str]) -> AppInfo {
+        let mut app = AppInfo::from_parts(
+            AppName::new(name),
+            PathBuf::new(),
+            WindowClass::new(class),
+            ExecCommand::new(class),
+        );
+        app.keywords = keywords.iter().map(|k| k.to_string()).collect();
+        app
+    }
+
+    #[test]
+    fn test_find_by_keyword() {
+        let entries = vec![
+            entry("Firefox", "firefox", &["web", "internet", "browser"]),
+            entry("Internet", "internet-radio", &[]),
+            entry("Zen Browser", "zen", &["web", "browser"]),
+        ];
+        let find = |query: &str| {
+            find_desktop_entry_in_list(&AppQuery::new(query), &entries).map(|app| app.name)
+        };
+
+        // only a keyword matches, so the first entry that has it wins
+        assert_eq!(find("Browser").unwrap().as_str(), "Firefox");
+        // a name beats a keyword, even on a later entry
+        assert_eq!(find("internet").unwrap().as_str(), "Internet");
+        assert_eq!(find("zen").unwrap().as_str(), "Zen Browser");
+        assert!(find("radio").is_none());
+    }
+
+    #[test]
+    fn test_strip_field_codes() {
+        let strip = |exec: &str| strip_field_codes(exec, Some("org.example.App"));
+
+        assert_eq!(strip("foo %U"), "foo");
+        assert_eq!(strip("app \"a b\" %f"), "app 'a b'");
+        assert_eq!(strip("viewer %f --new %u"), "viewer --new");
+        assert_eq!(strip("app %i %c"), "app --icon org.example.App");
+        assert_eq!(strip_field_codes("app %i", None), "app");
+        // `%%` should be a literal `%`, but any argument starting with `%` is dropped for now
+        assert_eq!(strip("x %%y"), "x");
+        // codes inside an argument are left alone
+        assert_eq!(strip("sh -c 'echo %f'"), "sh -c 'echo %f'");
+        // a line shell_words can't split is kept as written
+        assert_eq!(strip("app \"unterminated %U"), "app \"unterminated %U");
+    }
+
+    #[test]
+    fn test_filter_category() {
+        let mut entries = vec![
+            entry("zed", "dev.zed.Zed", &[]),
+            entry("Firefox", "firefox", &[]),
+            entry("Kate", "org.kde.kate", &[]),
+        ];
+        entries[0].categories = vec!["Development".into(), "IDE".into()];
+        entries[2].categories = vec!["Utility".into(), "development".into()];
+
+        filter_category(&mut entries, "DEVELOPMENT");
+        let names: Vec<_> = entries.iter().map(|app| app.name.as_str()).collect();
+        assert_eq!(names, ["Kate", "zed"]);
+    }
+
+    #[test]
+    fn test_parse_keywords() {
+        let path =
+            std::env::temp_dir().join(format!("hypraise-test-{}.desktop", std::process::id()));
+        fs::write(
+            &path,
+            "[Desktop Entry]\nType=Application\nName=Test\nExec=test %U\nKeywords=Web; Internet;;\nCategories=Network;WebBrowser;\n",
+        )
+        .unwrap();
+        let app = parse_desktop_file(&path);
+        let _ = fs::remove_file(&path);
+
+        let app = app.unwrap();
+        assert_eq!(app.keywords, ["web", "internet"]);
+        assert_eq!(app.categories, ["Network", "WebBrowser"]);
+        assert_eq!(app.exec.to_string(), "test");
+    }
+
+    #[test]
+    fn test_uris_and_paths_open_with_xdg_open() {
+        let program = |exec: &str| {
+            let command = Exec::from(ExecCommand::new(exec)).to_command().unwrap();
+            parts(&command)
+        };
+        let file = std::env::temp_dir().join(format!("hypraise-test-{}.txt", std::process::id()));
+        fs::write(&file, "").unwrap();
+        let file = file.to_string_lossy().into_owned();
+
+        assert_eq!(
+            program("https://example.com"),
+            ("xdg-open".into(), vec!["https://example.com".into()])
+        );
+        assert_eq!(program(&file), ("xdg-open".into(), vec![file.clone()]));
+        assert_eq!(program("/tmp").0, "xdg-open");
+        let _ = fs::remove_file(&file);
+
+        // executables and commands still run
+        assert_eq!(program("/bin/sh").0, "sh");
+        assert_eq!(program("firefox https://example.com").0, "sh");
+        assert_eq!(program("C:stuff").0, "sh");
+        assert_eq!(program("/no/such/file").0, "sh");
+    }
+
+    #[test]
+    fn test_fallback_chain_runs_first_installed() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = std::env::temp_dir().join(format!("hypraise-path-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for (program, mode) in [("wezterm", 0o644), ("kitty", 0o755), ("alacritty", 0o755)] {
+            let file = dir.join(program);
+            fs::write(&file, "").unwrap();
+            fs::set_permissions(&file, std::fs::Permissions::from_mode(mode)).unwrap();
+        }
+        let chain = |candidates: &[&str]| Exec::FirstOf {
+            first_of: candidates
+                .iter()
+                .map(|c| Exec::from(ExecCommand::new(*c)))
+                .collect(),
+        };
+        let resolved = |exec: &Exec| exec.resolve_in(dir.as_os_str()).map(|e| e.to_string());
+
+        // wezterm isn't executable, so it's skipped
+        let exec = chain(&["foot", "wezterm", "TERM=xterm kitty -1", "alacritty"]);
+        assert_eq!(resolved(&exec).as_deref(), Some("TERM=xterm kitty -1"));
+        let exec = Exec::FirstOf {
+            first_of: vec![
+                Exec::Args(vec!["alacritty".into(), "-e".into()]),
+                Exec::from(ExecCommand::new("kitty")),
+            ],
+        };
+        assert_eq!(resolved(&exec).as_deref(), Some("alacritty -e"));
+        let kitty = dir.join("kitty").to_string_lossy().into_owned();
+        assert_eq!(resolved(&chain(&["foot", &kitty])), Some(kitty));
+        assert_eq!(resolved(&chain(&["foot", "wezterm"])), None);
+        let _ = fs::remove_dir_all(&dir);
+
+        // a plain exec isn't checked, so a missing program still fails loudly when spawned
+        let plain = Exec::from(ExecCommand::new("foot"));
+        assert_eq!(plain.resolve_in(OsStr::new("")), Some(&plain));
+        assert_eq!(chain(&["a", "b"]).to_string(), "a || b");
+    }
+
+    #[test]
+    fn test_empty_exec_has_no_command() {
+        assert!(Exec::from(ExecCommand::new("  ")).to_command().is_none());
+        assert!(Exec::Args(Vec::new()).to_command().is_none());
+        assert!(Exec::Args(vec![String::new(), "arg".into()]).is_empty());
+    }
+}
@@ -1,12 +1,25 @@
 use derive_more::{AsRef, Deref, Display, From, Into};
 use freedesktop_icons::lookup;
+use parking_lot::RwLock;
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Display, Deref, From, Into, AsRef)]
 pub struct IconName(String);
 
 crate::impl_string_newtype!(IconName);
 
+static ICON_THEME: OnceLock<RwLock<Option<String>>> = OnceLock::new();
+
+/// Sets the icon theme used by later lookups; `None` falls back to `hicolor`.
+pub fn set_icon_theme(theme: Option<String>) {
+    *ICON_THEME.get_or_init(|| RwLock::new(None)).write() = theme;
+}
+
+pub fn icon_theme() -> Option<String> {
+    ICON_THEME.get().and_then(|lock| lock.read().clone())
+}
+
 pub fn find_icon_path(icon_name: &IconName) -> Option<PathBuf> {
     if icon_name.is_empty() {
         return None;
@@ -17,8 +30,10 @@ pub fn find_icon_path(icon_name: &IconName) -> Option<PathBuf> {
         return Some(path.to_path_buf());
     }
 
-    lookup(icon_name.as_ref())
-        .with_size(512)
-        .with_scale(1)
-        .find()
+    let theme = icon_theme();
+    let builder = lookup(icon_name.as_ref()).with_size(512).with_scale(1);
+    match &theme {
+        Some(theme) => builder.with_theme(theme).find(),
+        None => builder.find(),
+    }
 }
@@ -1,11 +1,15 @@
 use clap::{Parser, Subcommand};
 use hypraise::desktop::{AppInfo, AppQuery, ExecCommand};
-use hypraise::wm::{self, ShellCommand, WindowClass};
-use std::io::Write;
+use hypraise::wm::{self, WindowClass};
+use std::io::{BufRead, BufReader, Read, Write};
 use std::os::unix::net::UnixStream;
+use std::time::Duration;
 
 const SOCKET_PATH: &str = "/tmp/halo.sock";
 
+/// How long to wait for halo to refuse a command before taking its silence as success.
+const REPLY_TIMEOUT: Duration = Duration::from_millis(500);
+
 #[derive(Parser, Debug)]
 #[command(name = "hypraise", version, about, long_about = None)]
 struct Cli {
@@ -27,9 +31,44 @@ struct Cli {
 #[derive(Subcommand, Debug, Clone)]
 enum Commands {
     /// Show the Halo menu.
-    Show,
+    Show {
+        /// Show a ring of the apps in this desktop entry category instead, e.g. `Development`
+        #[arg(long, conflicts_with = "running_only")]
+        category: Option<String>,
+        /// Show only the slots whose app is running; Tab in the menu brings back the rest
+        #[arg(long)]
+        running_only: bool,
+        /// Center the ring here instead of on the cursor, as `x,y` in logical pixels from the
+        /// focused monitor's top-left, e.g. a bar button's position
+        #[arg(long, value_name = "X,Y", conflicts_with_all = ["category", "running_only"])]
+        at: Option<String>,
+        /// Multiply the ring's size by this, e.g. `0.7` for a compact ring
+        #[arg(long, conflicts_with_all = ["category", "running_only"])]
+        scale: Option<f64>,
+    },
+    /// Show only open windows for quick switching; `hide` focuses the selected one
+    Switch,
     /// Hide the Halo menu
     Hide,
+    /// Show the Halo menu until this process exits, e.g. when a key release bind kills it
+    Hold,
+    /// Re-resolve Halo's icons, e.g. after switching icon themes
+    RefreshIcons,
+    /// Re-read the desktop entries, e.g. after installing an app a slot couldn't find
+    Rescan,
+    /// Print Halo's open clients and subslot assignments as JSON
+    Subslots,
+    /// Print the config Halo is using as TOML, defaults and runtime overrides included
+    DumpConfig,
+    /// Print a starting config with a slot for each open app
+    ExportConfig,
+    /// Replace a slot's app until the config is next reloaded
+    SetSlot {
+        /// Slot direction, e.g. `north`, `ne` or `3`
+        direction: String,
+        /// The application name or window class
+        app: String,
+    },
 }
 
 fn main() -> anyhow::Result<()> {
@@ -37,8 +76,34 @@ fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
 
     match cli.command {
-        Some(Commands::Show) => send_command("show"),
+        Some(Commands::Show {
+            category: Some(category),
+            ..
+        }) => send_command(&format!("show-category {}", category)),
+        Some(Commands::Show {
+            running_only: true, ..
+        }) => send_command("show-running"),
+        Some(Commands::Show { at, scale, .. }) => {
+            let mut command = "show".to_string();
+            if let Some(at) = at {
+                command += &format!(" --at {at}");
+            }
+            if let Some(scale) = scale {
+                command += &format!(" --scale {scale}");
+            }
+            send_command(&command)
+        }
+        Some(Commands::Switch) => send_command("switch"),
         Some(Commands::Hide) => send_command("hide"),
+        Some(Commands::Hold) => hold(),
+        Some(Commands::RefreshIcons) => send_command("refresh-icons"),
+        Some(Commands::Rescan) => send_command("rescan"),
+        Some(Commands::Subslots) => query("subslots"),
+        Some(Commands::DumpConfig) => query("dump-config"),
+        Some(Commands::ExportConfig) => query("export-config"),
+        Some(Commands::SetSlot { direction, app }) => {
+            send_command(&format!("set-slot {} {}", direction, app))
+        }
         None => {
             if let Some(query) = cli.name {
                 run_or_raise(query, cli.class, cli.exec)
@@ -55,9 +120,14 @@ fn run_or_raise(query: String, class: Option<String>, exec: Option<String>) -> a
     let app = AppInfo::new(
         &AppQuery::from(query.clone()),
         class.map(WindowClass::new),
-        exec.map(ExecCommand::from),
+        exec.map(|e| ExecCommand::from(e).into()),
     );
 
+    if app.exec.is_setup() {
+        // the daemon knows where the config lives and how to create it
+        return send_command("setup");
+    }
+
     if app.exec.is_empty() {
         anyhow::bail!(
             "Could not find a desktop entry for '{}' and no --exec was provided.",
@@ -65,19 +135,57 @@ fn run_or_raise(query: String, class: Option<String>, exec: Option<String>) -> a
         );
     }
 
-    wm::run_or_raise(&app.class, &ShellCommand::from(app.exec.to_string()))?;
+    wm::run_or_raise(&app.class, &app.exec, app.startup_notify)?;
     Ok(())
 }
 
-fn send_command(cmd: &str) -> anyhow::Result<()> {
-    let mut stream = UnixStream::connect(SOCKET_PATH).map_err(|e| {
+fn connect() -> anyhow::Result<UnixStream> {
+    UnixStream::connect(SOCKET_PATH).map_err(|e| {
         anyhow::anyhow!(
             "Failed to connect to halo daemon at {}: {}. Is halo running?",
             SOCKET_PATH,
             e
         )
-    })?;
+    })
+}
 
+/// Sends `cmd`, failing if halo answers it with an error. Other commands get no reply.
+fn send_command(cmd: &str) -> anyhow::Result<()> {
+    let mut stream = connect()?;
+    writeln!(stream, "{}", cmd)?;
+    stream.shutdown(std::net::Shutdown::Write)?;
+    stream.set_read_timeout(Some(REPLY_TIMEOUT))?;
+    let mut reply = String::new();
+    // a timeout means halo is still working on it, which it only does for valid commands
+    let _ = BufReader::new(&stream).read_line(&mut reply);
+    match reply.trim().strip_prefix("err: ") {
+        Some(e) => anyhow::bail!("halo refused '{}': {}", cmd, e),
+        None => Ok(()),
+    }
+}
+
+/// Sends a request and prints the whole reply, which may span several lines, unless halo
+/// answers it with an error.
+fn query(cmd: &str) -> anyhow::Result<()> {
+    let mut stream = connect()?;
     writeln!(stream, "{}", cmd)?;
+    // halo closes the connection once it has answered and sees no more requests
+    stream.shutdown(std::net::Shutdown::Write)?;
+    let mut reply = String::new();
+    stream.read_to_string(&mut reply)?;
+    if let Some(e) = reply.trim().strip_prefix("err: ") {
+        anyhow::bail!("halo couldn't answer '{}': {}", cmd, e);
+    }
+    print!("{}", reply);
+    Ok(())
+}
+
+/// Keeps the connection open so the menu stays up; halo hides it once the connection closes,
+/// whether because we were killed or because the daemon went away.
+fn hold() -> anyhow::Result<()> {
+    let mut stream = connect()?;
+    writeln!(stream, "hold")?;
+    // halo never writes back, so this only returns on EOF or error
+    let _ = stream.read(&mut [0u8; 1]);
     Ok(())
 }
@@ -1,10 +1,21 @@
+use crate::desktop::Exec;
 use derive_more::{AsRef, Deref, Display, From, Into};
-use hyprland::data::{Clients, CursorPosition, Monitors};
-use hyprland::dispatch::{Dispatch, DispatchType, WindowIdentifier};
+use fs_err as fs;
+use hyprland::data::{Binds, Client, Clients, CursorPosition, Monitors, Transforms, Workspace};
+use hyprland::dispatch::{
+    Dispatch, DispatchType, WindowIdentifier, WorkspaceIdentifierWithSpecial,
+};
 use hyprland::error::HyprError;
+use hyprland::event_listener::EventListener;
 use hyprland::prelude::*;
-use hyprland::shared::Address;
+pub use hyprland::shared::{Address, MonitorId};
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::process::Child;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use thiserror::Error;
 
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
@@ -54,8 +65,34 @@ pub enum RunOrRaiseError {
 pub struct ActiveClient {
     pub address: Address,
     pub class: WindowClass,
+    /// The class the window opened with, which some apps change afterwards
+    pub initial_class: WindowClass,
     pub title: ClientTitle,
     pub workspace_id: i32,
+    pub workspace_name: String,
+    /// The monitor the window is on, when Hyprland knows
+    pub monitor: Option<MonitorId>,
+    /// This is the window with keyboard focus
+    pub focused: bool,
+    /// The window asked for attention and hasn't been focused since
+    pub urgent: bool,
+}
+
+impl ActiveClient {
+    /// Special (scratchpad) workspaces are named `special` or `special:<name>`.
+    pub fn is_on_special_workspace(&self) -> bool {
+        self.workspace_name == "special" || self.workspace_name.starts_with("special:")
+    }
+
+    /// The classes the window is known by: its class, plus its initial class when
+    /// [`set_match_initial_class`] is on and that differs.
+    pub fn match_classes(&self) -> impl Iterator<Item = &WindowClass> {
+        let initial = (MATCH_INITIAL_CLASS.load(Ordering::Relaxed)
+            && !self.initial_class.is_empty()
+            && !self.initial_class.eq_ignore_ascii_case(&self.class))
+        .then_some(&self.initial_class);
+        std::iter::once(&self.class).chain(initial)
+    }
 }
 
 pub fn get_active_clients() -> Vec<ActiveClient> {
@@ -64,16 +101,51 @@ pub fn get_active_clients() -> Vec<ActiveClient> {
             clients
                 .into_iter()
                 .map(|c| ActiveClient {
+                    urgent: c.focus_history_id != 0 && is_urgent(&c.address),
                     address: c.address,
                     class: WindowClass(c.class),
+                    initial_class: WindowClass(c.initial_class),
                     title: ClientTitle(c.title),
                     workspace_id: c.workspace.id,
+                    workspace_name: c.workspace.name,
+                    monitor: c.monitor,
+                    focused: c.focus_history_id == 0,
                 })
                 .collect()
         })
         .unwrap_or_default()
 }
 
+/// Windows that asked for attention since they last had focus. Hyprland only reports this as
+/// an event, so it's collected by [`watch_urgent`].
+static URGENT: OnceLock<Mutex<HashSet<Address>>> = OnceLock::new();
+
+fn urgent() -> &'static Mutex<HashSet<Address>> {
+    URGENT.get_or_init(Default::default)
+}
+
+fn is_urgent(address: &Address) -> bool {
+    urgent().lock().contains(address)
+}
+
+/// Follows Hyprland's events to keep track of which windows want attention, blocking until
+/// the event socket closes. A window stops counting once it's focused or closed.
+pub fn watch_urgent() -> Result<(), HyprError> {
+    let mut listener = EventListener::new();
+    listener.add_urgent_state_changed_handler(|address| {
+        urgent().lock().insert(address);
+    });
+    listener.add_active_window_changed_handler(|window| {
+        if let Some(window) = window {
+            urgent().lock().remove(&window.address);
+        }
+    });
+    listener.add_window_closed_handler(|address| {
+        urgent().lock().remove(&address);
+    });
+    listener.start_listener()
+}
+
 pub fn get_active_classes() -> Vec<WindowClass> {
     Clients::get()
         .map(|clients| clients.into_iter().map(|c| WindowClass(c.class)).collect())
@@ -92,6 +164,12 @@ pub fn close_window(class: &WindowClass) -> Result<(), HyprError> {
     ))
 }
 
+pub fn close_window_by_address(address: &Address) -> Result<(), HyprError> {
+    Dispatch::call(DispatchType::CloseWindow(WindowIdentifier::Address(
+        address.clone(),
+    )))
+}
+
 pub fn get_active_monitor() -> Option<MonitorName> {
     Monitors::get()
         .ok()?
@@ -100,6 +178,19 @@ pub fn get_active_monitor() -> Option<MonitorName> {
         .map(|m| MonitorName(m.name))
 }
 
+/// The name of the focused workspace; numbered workspaces are named by their number.
+pub fn get_active_workspace() -> Option<String> {
+    Workspace::get_active().ok().map(|w| w.name)
+}
+
+pub fn get_active_monitor_id() -> Option<MonitorId> {
+    Monitors::get()
+        .ok()?
+        .into_iter()
+        .find(|m| m.focused)
+        .map(|m| m.id)
+}
+
 pub fn get_cursor_pos_on_active_monitor() -> Option<Point> {
     let cursor = CursorPosition::get().ok()?;
     let monitors = Monitors::get().ok()?;
@@ -111,40 +202,727 @@ pub fn get_cursor_pos_on_active_monitor() -> Option<Point> {
     Some(Point::new(x, y))
 }
 
-pub fn run_or_raise(class: &WindowClass, exec: &ShellCommand) -> Result<(), RunOrRaiseError> {
-    let target = class.0.to_ascii_lowercase();
+/// A monitor's area in Hyprland's layout coordinates, the ones the cursor position is in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MonitorRect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl MonitorRect {
+    /// The nearest point on the monitor to `point`, or `None` if it's already on it.
+    pub fn clamp(&self, point: Point) -> Option<Point> {
+        // the far edges belong to the next monitor over
+        let x = point.x.clamp(self.x, self.x + self.width - 1.0);
+        let y = point.y.clamp(self.y, self.y + self.height - 1.0);
+        (x != point.x || y != point.y).then_some(Point::new(x, y))
+    }
+}
+
+/// The focused monitor's area. Hyprland reports its size in pixels before scaling and
+/// rotation, so both are undone here.
+pub fn get_active_monitor_rect() -> Option<MonitorRect> {
+    let monitor = Monitors::get().ok()?.into_iter().find(|m| m.focused)?;
+    let scale = if monitor.scale > 0.0 {
+        monitor.scale as f64
+    } else {
+        1.0
+    };
+    let (width, height) = (monitor.width as f64 / scale, monitor.height as f64 / scale);
+    let rotated = matches!(
+        monitor.transform,
+        Transforms::Normal90
+            | Transforms::Normal270
+            | Transforms::Flipped90
+            | Transforms::Flipped270
+    );
+    let (width, height) = if rotated {
+        (height, width)
+    } else {
+        (width, height)
+    };
+    Some(MonitorRect {
+        x: monitor.x as f64,
+        y: monitor.y as f64,
+        width,
+        height,
+    })
+}
+
+/// Warps the cursor back onto `rect` if it has left it. Returns whether it had to.
+pub fn confine_cursor(rect: &MonitorRect) -> bool {
+    let Ok(cursor) = CursorPosition::get() else {
+        return false;
+    };
+    let Some(inside) = rect.clamp(Point::new(cursor.x as f64, cursor.y as f64)) else {
+        return false;
+    };
+    Dispatch::call(DispatchType::MoveCursor(
+        inside.x.round() as i64,
+        inside.y.round() as i64,
+    ))
+    .inspect_err(|e| log::debug!("Failed to warp the cursor back: {}", e))
+    .is_ok()
+}
+
+/// A keybind from `hyprctl binds`, kept to what's needed to show it as a hint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyBind {
+    pub modmask: u16,
+    pub key: String,
+    pub dispatcher: String,
+    pub arg: String,
+}
 
-    #[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
-    enum MatchScore {
-        NoMatch,
-        Fuzzy,
-        Component,
-        Exact,
+impl KeyBind {
+    /// Formats the bind the way Hyprland configs spell it, e.g. `SUPER+SHIFT+F`.
+    pub fn label(&self) -> String {
+        // bit order from wlroots' `wlr_keyboard_modifier`; caps lock and mod2/3/5 are skipped
+        const MODS: [(u16, &str); 4] = [(64, "SUPER"), (4, "CTRL"), (8, "ALT"), (1, "SHIFT")];
+        MODS.iter()
+            .filter(|(bit, _)| self.modmask & bit != 0)
+            .map(|(_, name)| *name)
+            .chain(std::iter::once(self.key.as_str()))
+            .collect::<Vec<_>>()
+            .join("+")
+            .to_uppercase()
     }
-    Clients::get()?
+}
+
+/// Keyboard binds in the default submap. Best-effort: any IPC error yields no binds.
+pub fn get_binds() -> Vec<KeyBind> {
+    Binds::get()
+        .map(|binds| {
+            binds
+                .into_iter()
+                .filter(|b| !b.mouse && b.submap.is_empty())
+                .map(|b| KeyBind {
+                    modmask: b.modmask,
+                    key: b.key,
+                    dispatcher: b.dispatcher,
+                    arg: b.arg,
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Builds a startup-notification id in the `<unique>_TIME<timestamp>` form the spec suggests.
+fn startup_id() -> String {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or_default();
+    format!("hypraise-{}_TIME{}", std::process::id(), millis)
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+enum MatchScore {
+    NoMatch,
+    Fuzzy,
+    Component,
+    Exact,
+}
+
+fn match_score(window_class: &str, target: &str) -> MatchScore {
+    let w_class = window_class.to_ascii_lowercase();
+    match w_class {
+        ref s if s == target => MatchScore::Exact,
+        ref s if s.split('.').any(|p| p == target) => MatchScore::Component,
+        ref s if s.contains(target) || target.contains(s.as_str()) => MatchScore::Fuzzy,
+        _ => MatchScore::NoMatch,
+    }
+}
+
+/// Set by [`set_match_initial_class`] to also match windows by the class they opened with.
+static MATCH_INITIAL_CLASS: AtomicBool = AtomicBool::new(false);
+
+/// Also matches windows by their `initialClass`, for apps that rename their class after
+/// launch. Off by default, since it can match more windows than intended.
+pub fn set_match_initial_class(enabled: bool) {
+    MATCH_INITIAL_CLASS.store(enabled, Ordering::Relaxed);
+}
+
+/// Scores a window by its class, or by the better of its class and initial class when
+/// `match_initial` is on.
+fn client_score(class: &str, initial_class: &str, target: &str, match_initial: bool) -> MatchScore {
+    let score = match_score(class, target);
+    if match_initial && !initial_class.is_empty() {
+        score.max(match_score(initial_class, target))
+    } else {
+        score
+    }
+}
+
+/// Processes we launched, keyed by the class they were launched for.
+static LAUNCHED: OnceLock<Mutex<HashMap<WindowClass, Vec<Child>>>> = OnceLock::new();
+
+/// Drops children that have exited, reaping them so they don't linger as zombies.
+fn prune_launched(launched: &mut HashMap<WindowClass, Vec<Child>>) {
+    for children in launched.values_mut() {
+        children.retain_mut(|c| matches!(c.try_wait(), Ok(None)));
+    }
+    launched.retain(|_, children| !children.is_empty());
+}
+
+fn launched_pids(class: &WindowClass) -> Vec<u32> {
+    let mut launched = LAUNCHED.get_or_init(Default::default).lock();
+    prune_launched(&mut launched);
+    launched
+        .get(class)
+        .map(|children| children.iter().map(Child::id).collect())
+        .unwrap_or_default()
+}
+
+fn parent_pid(pid: u32) -> Option<u32> {
+    let stat = fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+    // `pid (comm) state ppid ...`, where comm may itself contain spaces or parens
+    stat.rsplit_once(')')?
+        .1
+        .split_whitespace()
+        .nth(1)?
+        .parse()
+        .ok()
+}
+
+/// Whether `pid` is `ancestor` or one of its descendants. Wrappers like `sh`, Electron
+/// launchers and `flatpak run` mean the window often belongs to a grandchild.
+fn descends_from(pid: u32, ancestor: u32) -> bool {
+    let mut current = pid;
+    for _ in 0..32 {
+        if current == ancestor {
+            return true;
+        }
+        match parent_pid(current) {
+            Some(parent) if parent > 1 => current = parent,
+            _ => return false,
+        }
+    }
+    false
+}
+
+/// Finds the open window that best matches `class`. Windows owned by a process we launched
+/// for this class win outright; otherwise classes are scored: exact, then a dotted component
+/// (`org.mozilla.firefox` for `firefox`), then a substring either way. With
+/// [`set_match_initial_class`] on, the initial class is scored as well.
+fn find_best_client(class: &WindowClass) -> Result<Option<Client>, HyprError> {
+    let mut clients: Vec<Client> = Clients::get()?.into_iter().collect();
+
+    let pids = launched_pids(class);
+    if let Some(idx) = clients
+        .iter()
+        .position(|c| c.pid > 0 && pids.iter().any(|&pid| descends_from(c.pid as u32, pid)))
+    {
+        return Ok(Some(clients.swap_remove(idx)));
+    }
+
+    let target = class.0.to_ascii_lowercase();
+    let match_initial = MATCH_INITIAL_CLASS.load(Ordering::Relaxed);
+    Ok(clients
         .into_iter()
         .map(|c| {
-            let w_class = c.class.to_ascii_lowercase();
-            let score = match w_class {
-                ref s if s == &target => MatchScore::Exact,
-                ref s if s.split('.').any(|p| p == target) => MatchScore::Component,
-                ref s if s.contains(&target) || target.contains(s) => MatchScore::Fuzzy,
-                _ => MatchScore::NoMatch,
-            };
+            let score = client_score(&c.class, &c.initial_class, &target, match_initial);
             (score, c)
         })
         .filter(|(score, _)| *score > MatchScore::NoMatch)
         .max_by_key(|(score, _)| *score)
-        .map_or_else(
+        .map(|(_, c)| c))
+}
+
+/// Set by [`set_systemd_scope`] to start apps in their own systemd scope.
+static USE_SYSTEMD_SCOPE: AtomicBool = AtomicBool::new(false);
+
+/// Launches each app in a transient `systemd-run --user --scope` unit when `systemd-run` is
+/// installed, so it gets its own cgroup and outlives the process that launched it. Units are
+/// named after the app's class, see [`scope_unit_name`].
+pub fn set_systemd_scope(enabled: bool) {
+    USE_SYSTEMD_SCOPE.store(enabled, Ordering::Relaxed);
+}
+
+fn systemd_run_available() -> bool {
+    static AVAILABLE: OnceLock<bool> = OnceLock::new();
+    *AVAILABLE.get_or_init(|| {
+        std::env::var_os("PATH")
+            .is_some_and(|path| crate::desktop::find_program("systemd-run", &path).is_some())
+    })
+}
+
+/// Wraps `command` in `systemd-run --scope`, which execs it in place, so the PID stays the
+/// app's own.
+fn in_systemd_scope(command: &std::process::Command, unit: Option<&str>) -> std::process::Command {
+    let mut scoped = std::process::Command::new("systemd-run");
+    scoped.args(["--user", "--scope", "--quiet", "--collect"]);
+    if let Some(unit) = unit {
+        scoped.arg(format!("--unit={unit}"));
+    }
+    scoped
+        .arg("--")
+        .arg(command.get_program())
+        .args(command.get_args());
+    scoped
+}
+
+/// Names the scope of an app launched for `class`, so it's easy to find in `systemctl --user`:
+/// `halo-<class>.scope`, or `halo-<class>-2.scope` and so on past the ones `loaded` lists
+/// for a `halo-<class>*` pattern. Characters systemd doesn't allow become `-`. `None` leaves
+/// the name to systemd, for a window class that's empty.
+fn scope_unit_name(
+    class: &WindowClass,
+    loaded: impl FnOnce(&str) -> Vec<String>,
+) -> Option<String> {
+    let base: String = class
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '_' | '-' => c.to_ascii_lowercase(),
+            _ => '-',
+        })
+        .collect();
+    if base.trim_matches('-').is_empty() {
+        return None;
+    }
+    let taken = loaded(&format!("halo-{base}*"));
+    (1..)
+        .map(|n| match n {
+            1 => format!("halo-{base}.scope"),
+            n => format!("halo-{base}-{n}.scope"),
+        })
+        .find(|unit| !taken.contains(unit))
+}
+
+/// The user units matching `pattern`, in one `systemctl` call. Empty if it can't be asked.
+fn loaded_units(pattern: &str) -> Vec<String> {
+    std::process::Command::new("systemctl")
+        .args(["--user", "list-units", "--plain", "--no-legend", pattern])
+        .stderr(std::process::Stdio::null())
+        .output()
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .filter_map(|line| line.split_whitespace().next())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// How long [`spawn_detached`] waits before each retry of a spawn that failed transiently.
+const SPAWN_RETRY_DELAYS: [Duration; 2] = [Duration::from_millis(20), Duration::from_millis(100)];
+
+/// Spawns `command` with its stdio set to `/dev/null`. A spawn that fails because the system is
+/// briefly out of processes or memory, as under fork pressure, is retried after each of
+/// [`SPAWN_RETRY_DELAYS`] before giving up.
+pub fn spawn_detached(command: &mut std::process::Command) -> std::io::Result<Child> {
+    command
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null());
+    spawn_with_retries(|| command.spawn(), &SPAWN_RETRY_DELAYS)
+}
+
+fn spawn_with_retries<T>(
+    mut spawn: impl FnMut() -> std::io::Result<T>,
+    delays: &[Duration],
+) -> std::io::Result<T> {
+    use std::io::ErrorKind;
+
+    let mut delays = delays.iter();
+    loop {
+        match spawn() {
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    ErrorKind::WouldBlock | ErrorKind::Interrupted | ErrorKind::OutOfMemory
+                ) =>
+            {
+                let Some(delay) = delays.next() else {
+                    return Err(std::io::Error::new(
+                        e.kind(),
+                        format!("{e}, still after retrying"),
+                    ));
+                };
+                log::debug!("Spawn failed ({}), retrying in {:?}", e, delay);
+                std::thread::sleep(*delay);
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Spawns `exec` detached from our stdio, and remembers the child so its window can be found
+/// by PID later. With `startup_notify`, the process gets a `DESKTOP_STARTUP_ID` so the
+/// compositor can tie its first window back to this activation.
+fn launch(class: &WindowClass, exec: &Exec, startup_notify: bool) -> std::io::Result<()> {
+    if exec.is_setup() {
+        // `sh -c HALO_SETUP` would only fail in the background
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("'{exec}' opens halo's config and can't be run as a command"),
+        ));
+    }
+    let mut command = exec.to_command().ok_or_else(|| {
+        if exec.resolve().is_none() {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("none of '{exec}' is installed"),
+            )
+        } else {
+            std::io::Error::new(std::io::ErrorKind::InvalidInput, "no command to launch")
+        }
+    })?;
+    if USE_SYSTEMD_SCOPE.load(Ordering::Relaxed) && systemd_run_available() {
+        let unit = scope_unit_name(class, loaded_units);
+        command = in_systemd_scope(&command, unit.as_deref());
+    }
+    if startup_notify {
+        command.env("DESKTOP_STARTUP_ID", startup_id());
+    } else {
+        // don't leak the id halo itself was started with
+        command.env_remove("DESKTOP_STARTUP_ID");
+    }
+    let child = spawn_detached(&mut command)?;
+
+    let mut launched = LAUNCHED.get_or_init(Default::default).lock();
+    prune_launched(&mut launched);
+    launched.entry(class.clone()).or_default().push(child);
+    Ok(())
+}
+
+/// Whether a window matching `class` is open, by the same rules [`run_or_raise`] uses to
+/// pick the window it raises.
+pub fn is_running(class: &WindowClass) -> bool {
+    find_best_client(class).is_ok_and(|client| client.is_some())
+}
+
+/// Focuses the best-matching window for `class`, or launches `exec` if none is open.
+pub fn run_or_raise(
+    class: &WindowClass,
+    exec: &Exec,
+    startup_notify: bool,
+) -> Result<(), RunOrRaiseError> {
+    match find_best_client(class)? {
+        Some(client) => Ok(focus_window(&client.address)?),
+        None => Ok(launch(class, exec, startup_notify)?),
+    }
+}
+
+/// Focuses the best-matching window for `class` without launching anything. Returns whether
+/// there was one.
+pub fn raise(class: &WindowClass) -> Result<bool, HyprError> {
+    match find_best_client(class)? {
+        Some(client) => focus_window(&client.address).map(|_| true),
+        None => Ok(false),
+    }
+}
+
+/// Starts another instance of `exec`, even if a window for `class` is already open.
+pub fn launch_new(
+    class: &WindowClass,
+    exec: &Exec,
+    startup_notify: bool,
+) -> Result<(), RunOrRaiseError> {
+    Ok(launch(class, exec, startup_notify)?)
+}
+
+/// How long [`run_single_instance`] waits for a window from an earlier launch to show up.
+const PENDING_LAUNCH_POLLS: u32 = 5;
+const PENDING_LAUNCH_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Like [`run_or_raise`], but never starts a second process for `class`. If an earlier
+/// launch is still running without a window, its window is polled for briefly, and nothing
+/// new is spawned if it doesn't appear yet.
+pub fn run_single_instance(
+    class: &WindowClass,
+    exec: &Exec,
+    startup_notify: bool,
+) -> Result<(), RunOrRaiseError> {
+    if let Some(client) = find_best_client(class)? {
+        return Ok(focus_window(&client.address)?);
+    }
+    if launched_pids(class).is_empty() {
+        return Ok(launch(class, exec, startup_notify)?);
+    }
+
+    for _ in 0..PENDING_LAUNCH_POLLS {
+        std::thread::sleep(PENDING_LAUNCH_INTERVAL);
+        if let Some(client) = find_best_client(class)? {
+            return Ok(focus_window(&client.address)?);
+        }
+    }
+    log::debug!("'{}' is still starting, not launching another", class);
+    Ok(())
+}
+
+/// Special workspace windows are parked on by [`toggle_app`].
+pub const HIDDEN_WORKSPACE: &str = "halo";
+
+/// Like [`run_or_raise`], but if the matching window is already focused it is hidden on the
+/// `special:halo` workspace instead. Activating a hidden window brings it back to the current
+/// workspace.
+pub fn toggle_app(
+    class: &WindowClass,
+    exec: &Exec,
+    startup_notify: bool,
+) -> Result<(), RunOrRaiseError> {
+    let Some(client) = find_best_client(class)? else {
+        return Ok(launch(class, exec, startup_notify)?);
+    };
+
+    let focused = Client::get_active()?.is_some_and(|active| active.address == client.address);
+    if focused {
+        return Ok(hide_window(&client.address)?);
+    }
+
+    if client.workspace.name == format!("special:{HIDDEN_WORKSPACE}") {
+        let workspace = Workspace::get_active()?;
+        Dispatch::call(DispatchType::MoveToWorkspaceSilent(
+            WorkspaceIdentifierWithSpecial::Id(workspace.id),
+            Some(WindowIdentifier::Address(client.address.clone())),
+        ))?;
+    }
+    Ok(focus_window(&client.address)?)
+}
+
+/// The workspace the best-matching window for `class` is on, by name.
+pub fn workspace_of(class: &WindowClass) -> Option<String> {
+    find_best_client(class)
+        .ok()
+        .flatten()
+        .map(|client| client.workspace.name)
+}
+
+/// Reads a workspace the way Hyprland names them: a number, `special` or `special:<name>`,
+/// or any other name.
+fn workspace_identifier(workspace: &str) -> WorkspaceIdentifierWithSpecial<'_> {
+    if let Ok(id) = workspace.parse() {
+        WorkspaceIdentifierWithSpecial::Id(id)
+    } else if workspace == "special" {
+        WorkspaceIdentifierWithSpecial::Special(None)
+    } else if let Some(name) = workspace.strip_prefix("special:") {
+        WorkspaceIdentifierWithSpecial::Special(Some(name))
+    } else {
+        WorkspaceIdentifierWithSpecial::Name(workspace)
+    }
+}
+
+/// Moves the window at `address` to `workspace`, following it there.
+pub fn move_window_to_workspace(address: &Address, workspace: &str) -> Result<(), HyprError> {
+    Dispatch::call(DispatchType::MoveToWorkspace(
+        workspace_identifier(workspace),
+        Some(WindowIdentifier::Address(address.clone())),
+    ))
+}
+
+pub fn hide_window(address: &Address) -> Result<(), HyprError> {
+    Dispatch::call(DispatchType::MoveToWorkspaceSilent(
+        WorkspaceIdentifierWithSpecial::Special(Some(HIDDEN_WORKSPACE)),
+        Some(WindowIdentifier::Address(address.clone())),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_descends_from_parent() {
+        let me = std::process::id();
+        let parent = std::os::unix::process::parent_id();
+
+        assert_eq!(parent_pid(me), Some(parent));
+        assert!(descends_from(me, me));
+        assert!(descends_from(me, parent));
+        assert!(!descends_from(parent, me));
+    }
+
+    #[test]
+    fn test_systemd_scope_wraps_command() {
+        let exec = Exec::from(crate::desktop::ExecCommand::new("firefox --new-window"));
+        let scoped = in_systemd_scope(&exec.to_command().unwrap(), Some("halo-firefox.scope"));
+        let args: Vec<_> = scoped.get_args().map(|a| a.to_str().unwrap()).collect();
+        assert_eq!(scoped.get_program(), "systemd-run");
+        assert_eq!(
+            args,
+            [
+                "--user",
+                "--scope",
+                "--quiet",
+                "--collect",
+                "--unit=halo-firefox.scope",
+                "--",
+                "sh",
+                "-c",
+                "firefox --new-window"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_client_score_initial_class() {
+        // a browser that renamed itself after launch
+        let score = |match_initial| client_score("Navigator", "firefox", "firefox", match_initial);
+        assert_eq!(score(false), MatchScore::NoMatch);
+        assert_eq!(score(true), MatchScore::Exact);
+        // the better of the two wins
+        assert_eq!(
+            client_score("org.mozilla.firefox", "fire", "firefox", true),
+            MatchScore::Component
+        );
+        assert_eq!(client_score("kitty", "", "kitty", true), MatchScore::Exact);
+    }
+
+    #[test]
+    fn test_scope_unit_name() {
+        let class = WindowClass::from("org.mozilla.Firefox".to_string());
+        let none_taken = |_: &str| Vec::new();
+        assert_eq!(
+            scope_unit_name(&class, none_taken).as_deref(),
+            Some("halo-org.mozilla.firefox.scope")
+        );
+
+        // a second instance gets the next free number, asking once for the app's units
+        let taken = |pattern: &str| {
+            assert_eq!(pattern, "halo-org.mozilla.firefox*");
+            [
+                "halo-org.mozilla.firefox.scope",
+                "halo-org.mozilla.firefox-3.scope",
+            ]
+            .map(String::from)
+            .to_vec()
+        };
+        assert_eq!(
+            scope_unit_name(&class, taken).as_deref(),
+            Some("halo-org.mozilla.firefox-2.scope")
+        );
+
+        let spaced = WindowClass::from("Steam Big Picture".to_string());
+        assert_eq!(
+            scope_unit_name(&spaced, none_taken).as_deref(),
+            Some("halo-steam-big-picture.scope")
+        );
+        assert_eq!(
+            scope_unit_name(&WindowClass::from(String::new()), none_taken),
+            None
+        );
+    }
+
+    #[test]
+    fn test_monitor_rect_clamp() {
+        let rect = MonitorRect {
+            x: 1920.0,
+            y: 0.0,
+            width: 2560.0,
+            height: 1440.0,
+        };
+        assert_eq!(rect.clamp(Point::new(2000.0, 700.0)), None);
+        assert_eq!(
+            rect.clamp(Point::new(1800.0, 700.0)),
+            Some(Point::new(1920.0, 700.0))
+        );
+        assert_eq!(
+            rect.clamp(Point::new(4480.0, -20.0)),
+            Some(Point::new(4479.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn test_setup_sentinel_is_not_launched() {
+        let setup = Exec::from(crate::desktop::ExecCommand::new(crate::desktop::SETUP_EXEC));
+        assert!(setup.is_setup());
+        assert!(!Exec::from(crate::desktop::ExecCommand::new("halo-setup")).is_setup());
+
+        let err = launch(&WindowClass::from("halo".to_string()), &setup, false).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_spawn_retries_transient_failures() {
+        use std::io::{Error, ErrorKind};
+
+        let delays = [Duration::ZERO; 2];
+        let mut attempts = 0;
+        let spawned = spawn_with_retries(
+            || {
+                attempts += 1;
+                if attempts < 3 {
+                    Err(Error::from(ErrorKind::WouldBlock))
+                } else {
+                    Ok(attempts)
+                }
+            },
+            &delays,
+        );
+        assert_eq!(spawned.unwrap(), 3);
+
+        // out of retries
+        let mut attempts = 0;
+        let err = spawn_with_retries::<()>(
+            || {
+                attempts += 1;
+                Err(Error::from(ErrorKind::WouldBlock))
+            },
+            &delays,
+        )
+        .unwrap_err();
+        assert_eq!(attempts, 3);
+        assert_eq!(err.kind(), ErrorKind::WouldBlock);
+
+        // a missing program won't turn up by waiting
+        let mut attempts = 0;
+        let err = spawn_with_retries::<()>(
             || {
-                std::process::Command::new("sh")
-                    .arg("-c")
-                    .arg(&exec.0)
-                    .stdout(std::process::Stdio::null())
-                    .stderr(std::process::Stdio::null())
-                    .spawn()?;
-                Ok(())
+                attempts += 1;
+                Err(Error::from(ErrorKind::NotFound))
             },
-            |(_, client)| focus_window(&client.address).map_err(RunOrRaiseError::from),
+            &delays,
         )
+        .unwrap_err();
+        assert_eq!(attempts, 1);
+        assert_eq!(err.kind(), ErrorKind::NotFound);
+    }
+
+    #[test]
+    fn test_workspace_identifier() {
+        let id = |ws: &str| workspace_identifier(ws).to_string();
+        assert_eq!(id("3"), "3");
+        assert_eq!(id("special"), "special");
+        assert_eq!(id("special:scratch"), "special:scratch");
+        assert_eq!(id("web"), "name:web");
+    }
+
+    #[test]
+    fn test_is_on_special_workspace() {
+        let on = |workspace: &str| ActiveClient {
+            address: Address::new("0x1"),
+            class: WindowClass::new("kitty"),
+            initial_class: WindowClass::new("kitty"),
+            title: ClientTitle::new(""),
+            workspace_id: -98,
+            workspace_name: workspace.to_string(),
+            monitor: None,
+            focused: false,
+            urgent: false,
+        };
+        assert!(on("special").is_on_special_workspace());
+        assert!(on("special:scratch").is_on_special_workspace());
+        assert!(!on("specialist").is_on_special_workspace());
+        assert!(!on("3").is_on_special_workspace());
+    }
+
+    #[test]
+    fn test_keybind_label() {
+        let bind = |modmask, key: &str| KeyBind {
+            modmask,
+            key: key.into(),
+            dispatcher: "exec".into(),
+            arg: String::new(),
+        };
+
+        assert_eq!(bind(64, "f").label(), "SUPER+F");
+        assert_eq!(bind(64 | 1, "Return").label(), "SUPER+SHIFT+RETURN");
+        // caps lock doesn't show
+        assert_eq!(bind(2 | 8, "1").label(), "ALT+1");
+        assert_eq!(bind(0, "F12").label(), "F12");
+    }
 }